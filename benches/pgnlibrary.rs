@@ -3,7 +3,8 @@ extern crate lazy_static;
 extern crate canparse;
 
 use canparse::pgn::*;
-use criterion::{black_box, criterion_group, criterion_main, Criterion as Bencher};
+use criterion::{criterion_group, criterion_main, Criterion as Bencher};
+use std::hint::black_box;
 
 lazy_static! {
     static ref SPNDEF: SpnDefinition = SpnDefinition::new(
@@ -1,5 +1,7 @@
 use crate::dbc;
+use crate::signal::{active_signals, parse_raw, parse_signals};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Trait for converting `Entry` values into a library's own entries.
 pub trait FromDbc {
@@ -15,7 +17,7 @@ pub trait FromDbc {
     fn merge_entry(&mut self, entry: dbc::Entry) -> Result<(), Self::Err>;
 }
 
-type SignalAttribute = String;
+type SignalAttribute = dbc::AttributeValue;
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Signal {
     /// e.g., {"SPN", "190"}
@@ -30,10 +32,39 @@ pub struct Signal {
 
     /// Only applicable for enum types
     /// e.g., VAL_ 2364540158 ActlEngPrcntTrqueHighResolution 8 "1111NotAvailable" 7 "0875" 1 "0125" 0 "0000" ;
-    value_definition: Option<dbc::ValueDefinition>,
+    value_definition: Option<Arc<dbc::ValueDefinition>>,
+    /// Name of the shared `VAL_TABLE_` backing `value_definition`, if any.
+    value_table: Option<String>,
+    /// Only applicable for extended multiplexing
+    /// e.g., SG_MUL_VAL_ 2566848512 PropDiag_FaultCode PropDiag_Status 4-5;
+    multiplex_selector: Option<dbc::MultiplexSelector>,
 }
 
-type MessageAttribute = String;
+type NodeAttribute = dbc::AttributeValue;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Node {
+    name: String,
+
+    /// e.g., BA_ "NmStationAddress" BU_ Engine 0;
+    attributes: HashMap<String, NodeAttribute>,
+    /// e.g., CM_ BU_ Engine "Engine control module";
+    description: Option<String>,
+}
+
+type EnvironmentVariableAttribute = dbc::AttributeValue;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EnvironmentVariable {
+    name: String,
+
+    /// e.g., BA_ "EnvStartValue" EV_ IgnitionSwitch 2;
+    attributes: HashMap<String, EnvironmentVariableAttribute>,
+    /// e.g., CM_ EV_ IgnitionSwitch "Ignition key position";
+    description: Option<String>,
+}
+
+type MessageAttribute = dbc::AttributeValue;
 
 #[derive(Clone, Debug, Default)]
 pub struct Message {
@@ -48,6 +79,122 @@ pub struct Message {
     signals: HashMap<String, Signal>,
 }
 
+impl FromDbc for Node {
+    type Err = ();
+
+    fn from_entry(entry: dbc::Entry) -> Result<Self, Self::Err>
+    where
+        Self: Sized,
+    {
+        match entry {
+            Entry::CanNodesDescription(dbc::CanNodesDescription {
+                node_name,
+                description,
+            }) => Ok(Node {
+                name: node_name,
+                description: Some(description),
+                ..Default::default()
+            }),
+            Entry::CanNodesAttribute(dbc::CanNodesAttribute {
+                name,
+                node_name,
+                value,
+            }) => {
+                let mut attributes = HashMap::new();
+                attributes.insert(name, value);
+
+                Ok(Node {
+                    name: node_name,
+                    attributes: attributes,
+                    ..Default::default()
+                })
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn merge_entry(&mut self, entry: dbc::Entry) -> Result<(), Self::Err> {
+        match entry {
+            Entry::CanNodesDescription(dbc::CanNodesDescription {
+                node_name: _node_name,
+                description,
+            }) => {
+                self.description = Some(description);
+                Ok(())
+            }
+            Entry::CanNodesAttribute(dbc::CanNodesAttribute {
+                name,
+                node_name: _node_name,
+                value,
+            }) => {
+                if let Some(_previous_value) = self.attributes.insert(name, value) {
+                    // TODO: Warn that we somehow already had an existing entry
+                }
+                Ok(())
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+impl FromDbc for EnvironmentVariable {
+    type Err = ();
+
+    fn from_entry(entry: dbc::Entry) -> Result<Self, Self::Err>
+    where
+        Self: Sized,
+    {
+        match entry {
+            Entry::EnvironmentVariableDescription(dbc::EnvironmentVariableDescription {
+                env_var_name,
+                description,
+            }) => Ok(EnvironmentVariable {
+                name: env_var_name,
+                description: Some(description),
+                ..Default::default()
+            }),
+            Entry::EnvironmentVariableAttribute(dbc::EnvironmentVariableAttribute {
+                name,
+                env_var_name,
+                value,
+            }) => {
+                let mut attributes = HashMap::new();
+                attributes.insert(name, value);
+
+                Ok(EnvironmentVariable {
+                    name: env_var_name,
+                    attributes: attributes,
+                    ..Default::default()
+                })
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn merge_entry(&mut self, entry: dbc::Entry) -> Result<(), Self::Err> {
+        match entry {
+            Entry::EnvironmentVariableDescription(dbc::EnvironmentVariableDescription {
+                env_var_name: _env_var_name,
+                description,
+            }) => {
+                self.description = Some(description);
+                Ok(())
+            }
+            Entry::EnvironmentVariableAttribute(dbc::EnvironmentVariableAttribute {
+                name,
+                env_var_name: _env_var_name,
+                value,
+            }) => {
+                if let Some(_previous_value) = self.attributes.insert(name, value) {
+                    // TODO: Warn that we somehow already had an existing entry
+                }
+                Ok(())
+            }
+            _ => Err(()),
+        }
+    }
+}
+
 impl FromDbc for Message {
     type Err = ();
 
@@ -126,48 +273,48 @@ impl FromDbc for Message {
                 }
                 Ok(())
             }
-            Entry::SignalDefinition(inner) => {
-                if self.signals.contains_key(&inner.name) {
-                    (*self
-                        .signals
-                        .get_mut(&inner.name)
-                        .expect("Already checked for Signal key"))
-                    .merge_entry(Entry::SignalDefinition(inner))
-                } else {
+            Entry::SignalDefinition(inner) => match self.signals.get_mut(&inner.name) {
+                Some(signal) => signal.merge_entry(Entry::SignalDefinition(inner)),
+                None => {
                     let name = inner.name.clone();
                     let signal = Signal::from_entry(Entry::SignalDefinition(inner))?;
                     self.signals.insert(name, signal);
                     Ok(())
                 }
-            }
-            Entry::SignalDescription(inner) => {
-                if self.signals.contains_key(&inner.signal_name) {
-                    (*self
-                        .signals
-                        .get_mut(&inner.signal_name)
-                        .expect("Already checked for Signal key"))
-                    .merge_entry(Entry::SignalDescription(inner))
-                } else {
+            },
+            Entry::SignalDescription(inner) => match self.signals.get_mut(&inner.signal_name) {
+                Some(signal) => signal.merge_entry(Entry::SignalDescription(inner)),
+                None => {
                     let name = inner.signal_name.clone();
                     let signal = Signal::from_entry(Entry::SignalDescription(inner))?;
                     self.signals.insert(name, signal);
                     Ok(())
                 }
-            }
-            Entry::SignalAttribute(inner) => {
-                if self.signals.contains_key(&inner.signal_name) {
-                    (*self
-                        .signals
-                        .get_mut(&inner.signal_name)
-                        .expect("Already checked for Signal key"))
-                    .merge_entry(Entry::SignalAttribute(inner))
-                } else {
+            },
+            Entry::SignalAttribute(inner) => match self.signals.get_mut(&inner.signal_name) {
+                Some(signal) => signal.merge_entry(Entry::SignalAttribute(inner)),
+                None => {
                     let name = inner.signal_name.clone();
                     let signal = Signal::from_entry(Entry::SignalAttribute(inner))?;
                     self.signals.insert(name, signal);
                     Ok(())
                 }
-            }
+            },
+            Entry::ValueDescription(inner) => match self.signals.get_mut(&inner.signal_name) {
+                // `VAL_` refines an existing `SG_` definition
+                Some(signal) => signal.merge_entry(Entry::ValueDescription(inner)),
+                None => Err(()),
+            },
+            Entry::SignalValueType(inner) => match self.signals.get_mut(&inner.signal_name) {
+                // `SIG_VALTYPE_` refines an existing `SG_` definition
+                Some(signal) => signal.merge_entry(Entry::SignalValueType(inner)),
+                None => Err(()),
+            },
+            Entry::ExtendedMultiplexing(inner) => match self.signals.get_mut(&inner.signal_name) {
+                // `SG_MUL_VAL_` refines an existing `SG_` definition
+                Some(signal) => signal.merge_entry(Entry::ExtendedMultiplexing(inner)),
+                None => Err(()),
+            },
             _ => Err(()),
         }
     }
@@ -186,6 +333,8 @@ impl FromDbc for Signal {
                 description: None,
                 definition: Some(definition),
                 value_definition: None,
+                value_table: None,
+                multiplex_selector: None,
             }),
             Entry::SignalDescription(dbc::SignalDescription {
                 id: _id,
@@ -196,6 +345,8 @@ impl FromDbc for Signal {
                 description: Some(description),
                 definition: None,
                 value_definition: None,
+                value_table: None,
+                multiplex_selector: None,
             }),
             Entry::SignalAttribute(dbc::SignalAttribute {
                 name,
@@ -210,6 +361,8 @@ impl FromDbc for Signal {
                     description: None,
                     definition: None,
                     value_definition: None,
+                    value_table: None,
+                    multiplex_selector: None,
                 })
             }
             _ => Err(()),
@@ -241,17 +394,233 @@ impl FromDbc for Signal {
                 }
                 Ok(())
             }
+            Entry::ValueDescription(dbc::ValueDescription {
+                id: _id,
+                signal_name: _signal_name,
+                values,
+            }) => {
+                self.value_definition = Some(Arc::new(values));
+                self.value_table = None;
+                Ok(())
+            }
+            Entry::SignalValueType(dbc::SignalValueType {
+                id: _id,
+                signal_name: _signal_name,
+                value_type,
+            }) => match self.definition {
+                Some(ref mut definition) => {
+                    definition.value_type = value_type;
+                    Ok(())
+                }
+                None => Err(()),
+            },
+            Entry::ExtendedMultiplexing(dbc::ExtendedMultiplexing {
+                id: _id,
+                signal_name: _signal_name,
+                selector,
+            }) => {
+                self.multiplex_selector = Some(selector);
+                Ok(())
+            }
             _ => Err(()),
         }
     }
 }
 
+impl Node {
+    pub fn new(name: String) -> Self {
+        Node {
+            name,
+            ..Default::default()
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the value of a node attribute (`BA_ ... BU_`), if it exists.
+    pub fn attribute(&self, name: &str) -> Option<&NodeAttribute> {
+        self.attributes.get(name)
+    }
+}
+
+impl EnvironmentVariable {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the value of an environment variable attribute (`BA_ ... EV_`), if it exists.
+    pub fn attribute(&self, name: &str) -> Option<&EnvironmentVariableAttribute> {
+        self.attributes.get(name)
+    }
+}
+
+impl Message {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the name of the node which transmits the message.
+    pub fn sending_node(&self) -> &str {
+        &self.sending_node
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the value of an explicit message attribute (`BA_ ... BO_`), if it exists.  See
+    /// `DbcLibrary::message_attribute` for a lookup which falls back to the attribute default.
+    pub fn attribute(&self, name: &str) -> Option<&MessageAttribute> {
+        self.attributes.get(name)
+    }
+
+    /// Returns the integer value of an explicit `Int`, `Hex` or `Enum` message attribute.
+    pub fn attribute_i64(&self, name: &str) -> Option<i64> {
+        self.attribute(name).and_then(MessageAttribute::as_i64)
+    }
+
+    /// Returns the numeric value of an explicit `Int`, `Hex` or `Float` message attribute.
+    pub fn attribute_f64(&self, name: &str) -> Option<f64> {
+        self.attribute(name).and_then(MessageAttribute::as_f64)
+    }
+
+    /// Returns the text of an explicit `String` message attribute, or label of an `Enum` one.
+    pub fn attribute_str(&self, name: &str) -> Option<&str> {
+        self.attribute(name).and_then(MessageAttribute::as_str)
+    }
+
+    /// Returns a `Signal` entry reference, if it exists.
+    pub fn get_signal(&self, name: &str) -> Option<&Signal> {
+        self.signals.get(name)
+    }
+
+    /// Returns the top-level multiplexor signal (`M`) selecting the message's multiplexed
+    /// signals, if any.
+    pub fn multiplexor(&self) -> Option<&Signal> {
+        self.signals.values().find(|signal| {
+            signal
+                .definition
+                .as_ref()
+                .map(|def| def.multiplex_indicator)
+                == Some(dbc::MultiplexIndicator::Multiplexor)
+        })
+    }
+
+    /// Returns the signals present in the given CAN message slice: every plain signal, plus the
+    /// multiplexed signals whose multiplexor value matches the one in the frame.  Nested
+    /// multiplexors (`mNM`) and value ranges (`SG_MUL_VAL_`) are resolved up to the top-level
+    /// multiplexor.  Signals without a definition (`SG_`) are skipped.
+    pub fn active_signals(&self, msg: &[u8]) -> Vec<&Signal> {
+        let multiplexor = self
+            .multiplexor()
+            .and_then(Signal::definition)
+            .map(|def| def.name.as_str());
+
+        active_signals(&self.signals, multiplexor, |signal| {
+            let def = signal.definition.as_ref()?;
+            Some((
+                def.multiplex_indicator,
+                signal.multiplex_selector.as_ref(),
+                parse_raw(def.bit_len, def.start_bit, def.little_endian, msg).ok(),
+            ))
+        })
+    }
+
+    /// Parses CAN message slice into the `f32` values of its active signals, keyed by signal
+    /// name.
+    pub fn parse_message(&self, msg: &[u8]) -> HashMap<&str, f32> {
+        parse_signals(
+            self.active_signals(msg)
+                .into_iter()
+                .filter_map(Signal::definition)
+                .map(|def| (def.name.as_str(), def)),
+            msg,
+        )
+    }
+}
+
+impl Signal {
+    pub fn definition(&self) -> Option<&dbc::SignalDefinition> {
+        self.definition.as_ref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the value of an explicit signal attribute (`BA_ ... SG_`), if it exists.  See
+    /// `DbcLibrary::signal_attribute` for a lookup which falls back to the attribute default.
+    pub fn attribute(&self, name: &str) -> Option<&SignalAttribute> {
+        self.attributes.get(name)
+    }
+
+    /// Returns the integer value of an explicit `Int`, `Hex` or `Enum` signal attribute.
+    pub fn attribute_i64(&self, name: &str) -> Option<i64> {
+        self.attribute(name).and_then(SignalAttribute::as_i64)
+    }
+
+    /// Returns the numeric value of an explicit `Int`, `Hex` or `Float` signal attribute.
+    pub fn attribute_f64(&self, name: &str) -> Option<f64> {
+        self.attribute(name).and_then(SignalAttribute::as_f64)
+    }
+
+    /// Returns the text of an explicit `String` signal attribute, or label of an `Enum` one.
+    pub fn attribute_str(&self, name: &str) -> Option<&str> {
+        self.attribute(name).and_then(SignalAttribute::as_str)
+    }
+
+    /// Returns the enumerated value labels (`VAL_`) for the signal, if any.
+    pub fn value_definition(&self) -> Option<&dbc::ValueDefinition> {
+        self.value_definition.as_deref()
+    }
+
+    /// Returns the name of the `VAL_TABLE_` the signal's value labels resolve to, if any.
+    ///
+    /// DBC files don't reference value tables from `VAL_` entries, so this is a heuristic: the
+    /// signal resolves to a table with exactly the same labels, the one with the lowest name if
+    /// several tables match.
+    pub fn value_table(&self) -> Option<&str> {
+        self.value_table.as_deref()
+    }
+
+    /// Returns the extended multiplexing (`SG_MUL_VAL_`) selector for the signal, if any.
+    pub fn multiplex_selector(&self) -> Option<&dbc::MultiplexSelector> {
+        self.multiplex_selector.as_ref()
+    }
+}
+
 /// A struct that represents a CANdb file, and provides APIs for interacting
 /// with CAN messages and signals.
 #[derive(Clone, Debug, Default)]
 pub struct DbcLibrary {
     last_id: Option<u32>,
     messages: HashMap<u32, Message>,
+    /// e.g., VAL_TABLE_ StarterMode 15 "NotAvailable" 14 "Error" 0 "StartNotRqed" ;
+    value_tables: HashMap<String, Arc<dbc::ValueDefinition>>,
+    /// e.g., BU_: Engine Transmission
+    nodes: HashMap<String, Node>,
+    /// e.g., BA_DEF_ BO_ "GenMsgCycleTime" INT 0 65535;
+    attribute_definitions: HashMap<String, dbc::AttributeDefinition>,
+    /// e.g., BA_DEF_DEF_ "GenMsgCycleTime" 100;
+    attribute_defaults: HashMap<String, dbc::AttributeValue>,
+    /// e.g., BA_DEF_DEF_REL_ "GenSigTimeoutTime" 0;
+    relation_attribute_defaults: HashMap<String, dbc::AttributeValue>,
+    /// e.g., BA_ "ProtocolType" "J1939";
+    attributes: HashMap<String, dbc::AttributeValue>,
+    /// e.g., CM_ "J1939 sample database";
+    description: Option<String>,
+    /// e.g., CM_ EV_ IgnitionSwitch "Ignition key position";
+    environment_variables: HashMap<String, EnvironmentVariable>,
 }
 
 use encoding::all::ISO_8859_1;
@@ -261,8 +630,7 @@ use std::io;
 use std::io::prelude::*;
 use std::path::Path;
 
-use super::parser;
-use crate::dbc::Entry;
+use crate::dbc::{load_entries, AttributeObjectType, Entry, LoadError, LoadErrorKind};
 
 impl DbcLibrary {
     /// Creates a new `DbcLibrary` instance given an existing lookup table.
@@ -270,6 +638,14 @@ impl DbcLibrary {
         DbcLibrary {
             last_id: None,
             messages,
+            value_tables: HashMap::new(),
+            nodes: HashMap::new(),
+            attribute_definitions: HashMap::new(),
+            attribute_defaults: HashMap::new(),
+            relation_attribute_defaults: HashMap::new(),
+            attributes: HashMap::new(),
+            description: None,
+            environment_variables: HashMap::new(),
         }
     }
 
@@ -298,8 +674,6 @@ impl DbcLibrary {
         P: AsRef<Path>,
         E: Encoding,
     {
-        let mut lib = DbcLibrary::default();
-
         let data = File::open(path)
             .and_then(|mut f| {
                 let mut contents: Vec<u8> = Vec::new();
@@ -311,31 +685,167 @@ impl DbcLibrary {
                     .map_err(io::Error::other)
             })?;
 
-        let mut i = data.as_str();
-        while !i.is_empty() {
-            match parser::entry(i) {
-                Ok((new_i, entry)) => {
-                    if let Err(_e) = lib.add_entry(entry) {
-                        // TODO: Handle add_entry error
-                    }
-                    i = new_i;
+        Ok(Self::load_dbc_str(&data).0)
+    }
+
+    /// Loads an entire DBC file into a returned `DbcLibrary`, like `from_dbc_file`, along with
+    /// warnings for the lines which were skipped or couldn't be added to the library.  Entries
+    /// which don't apply to `DbcLibrary` (such as `Entry::Version`) aren't reported.  Fails on
+    /// `io::Error`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use canparse::dbc::DbcLibrary;
+    ///
+    /// let (lib, warnings) = DbcLibrary::load_dbc_file("./tests/data/sample.dbc").unwrap();
+    /// assert!(lib.get_message(2364539904).is_some());
+    /// assert!(warnings.is_empty());
+    /// ```
+    pub fn load_dbc_file<P>(path: P) -> io::Result<(Self, Vec<LoadError>)>
+    where
+        P: AsRef<Path>,
+    {
+        let mut contents: Vec<u8> = Vec::new();
+        File::open(path)?.read_to_end(&mut contents)?;
+        let data = ISO_8859_1
+            .decode(contents.as_slice(), DecoderTrap::Replace)
+            .map_err(io::Error::other)?;
+
+        Ok(Self::load_dbc_str(&data))
+    }
+
+    /// Loads the contents of a DBC file into a returned `DbcLibrary`, along with warnings for
+    /// the lines which were skipped or couldn't be added to the library.  Never panics on
+    /// malformed input.
+    pub fn load_dbc_str(data: &str) -> (Self, Vec<LoadError>) {
+        let mut lib = DbcLibrary::default();
+        let warnings = load_entries(data, |entry| lib.add_entry(entry));
+
+        (lib, warnings)
+    }
+}
+
+/// Internal function for rejecting an entry which conflicts with the library's definitions.
+fn invalid_entry<S: Into<String>>(reason: S, entry: Entry) -> LoadError {
+    LoadError::new(LoadErrorKind::InvalidEntry(reason.into()), Some(entry))
+}
+
+impl DbcLibrary {
+    pub fn add_entry(&mut self, mut entry: Entry) -> Result<(), LoadError> {
+        let resolved = match entry {
+            Entry::CanNodesAttribute(dbc::CanNodesAttribute {
+                ref name,
+                ref mut value,
+                ..
+            }) => self.resolve_attribute(Some(AttributeObjectType::Node), name, value),
+            Entry::MessageAttribute(dbc::MessageAttribute {
+                ref name,
+                ref mut value,
+                ..
+            }) => self.resolve_attribute(Some(AttributeObjectType::Message), name, value),
+            Entry::SignalAttribute(dbc::SignalAttribute {
+                ref name,
+                ref mut value,
+                ..
+            }) => self.resolve_attribute(Some(AttributeObjectType::Signal), name, value),
+            Entry::NetworkAttribute(dbc::NetworkAttribute {
+                ref name,
+                ref mut value,
+            }) => self.resolve_attribute(Some(AttributeObjectType::Network), name, value),
+            Entry::EnvironmentVariableAttribute(dbc::EnvironmentVariableAttribute {
+                ref name,
+                ref mut value,
+                ..
+            }) => {
+                self.resolve_attribute(Some(AttributeObjectType::EnvironmentVariable), name, value)
+            }
+            Entry::AttributeDefault(dbc::AttributeDefault {
+                ref name,
+                ref mut value,
+            }) => self.resolve_attribute(None, name, value),
+            _ => Ok(()),
+        };
+        if let Err(reason) = resolved {
+            return Err(invalid_entry(reason, entry));
+        }
+
+        match entry {
+            Entry::AttributeDefinition(definition) => {
+                self.attribute_definitions
+                    .insert(definition.name.clone(), definition);
+                return Ok(());
+            }
+            Entry::AttributeDefault(dbc::AttributeDefault { name, value }) => {
+                self.attribute_defaults.insert(name, value);
+                return Ok(());
+            }
+            Entry::RelationAttributeDefault(dbc::AttributeDefault { name, value }) => {
+                // Relation attributes (`BA_DEF_REL_`) apply to node/message pairs, not to any
+                // single object, so their defaults are kept apart
+                self.relation_attribute_defaults.insert(name, value);
+                return Ok(());
+            }
+            Entry::NetworkDescription(dbc::NetworkDescription(description)) => {
+                self.description = Some(description);
+                return Ok(());
+            }
+            Entry::NetworkAttribute(dbc::NetworkAttribute { name, value }) => {
+                self.attributes.insert(name, value);
+                return Ok(());
+            }
+            Entry::ValueTable(dbc::ValueTable { name, values }) => {
+                self.value_tables.insert(name, Arc::new(values));
+                return Ok(());
+            }
+            Entry::CanNodes(dbc::CanNodes(names)) => {
+                for name in names {
+                    self.nodes
+                        .entry(name.clone())
+                        .or_insert_with(|| Node::new(name));
                 }
-                // FIXME: handling `IResult::Err`s could be better
-                Err(nom::Err::Incomplete(_)) => {
-                    break;
+                return Ok(());
+            }
+            Entry::CanNodesDescription(dbc::CanNodesDescription { ref node_name, .. })
+            | Entry::CanNodesAttribute(dbc::CanNodesAttribute { ref node_name, .. }) => {
+                let node_name = node_name.clone();
+                return match self.nodes.get_mut(&node_name) {
+                    Some(node) => node.merge_entry(entry.clone()),
+                    None => Node::from_entry(entry.clone()).map(|node| {
+                        self.nodes.insert(node_name, node);
+                    }),
+                }
+                .map_err(|_| invalid_entry("Failed to add node entry.", entry));
+            }
+            Entry::EnvironmentVariableDescription(dbc::EnvironmentVariableDescription {
+                ref env_var_name,
+                ..
+            })
+            | Entry::EnvironmentVariableAttribute(dbc::EnvironmentVariableAttribute {
+                ref env_var_name,
+                ..
+            }) => {
+                let env_var_name = env_var_name.clone();
+                return match self.environment_variables.get_mut(&env_var_name) {
+                    Some(env_var) => env_var.merge_entry(entry.clone()),
+                    None => EnvironmentVariable::from_entry(entry.clone()).map(|env_var| {
+                        self.environment_variables.insert(env_var_name, env_var);
+                    }),
                 }
-                Err(_) => {
-                    i = &i[1..];
+                .map_err(|_| invalid_entry("Failed to add environment variable entry.", entry));
+            }
+            Entry::SignalValueType(dbc::SignalValueType { id, .. })
+            | Entry::ExtendedMultiplexing(dbc::ExtendedMultiplexing { id, .. }) => {
+                // `SIG_VALTYPE_` and `SG_MUL_VAL_` refine an existing `SG_` definition
+                return match self.messages.get_mut(&id) {
+                    Some(message) => message.merge_entry(entry.clone()),
+                    None => Err(()),
                 }
+                .map_err(|_| LoadError::new(LoadErrorKind::MissingDefinition, Some(entry)));
             }
+            _ => {}
         }
 
-        Ok(lib)
-    }
-}
-
-impl DbcLibrary {
-    pub fn add_entry(&mut self, entry: Entry) -> Result<(), String> {
         let _id: u32 = *match entry {
             Entry::MessageDefinition(dbc::MessageDefinition { ref id, .. }) => id,
             Entry::MessageDescription(dbc::MessageDescription { ref id, .. }) => id,
@@ -345,38 +855,268 @@ impl DbcLibrary {
                 if let Some(last_id) = self.last_id.as_ref() {
                     last_id
                 } else {
-                    return Err("Tried to add SignalDefinition without last ID.".to_string());
+                    return Err(LoadError::new(
+                        LoadErrorKind::MissingDefinition,
+                        Some(entry),
+                    ));
                 }
             }
             Entry::SignalDescription(dbc::SignalDescription { ref id, .. }) => id,
             Entry::SignalAttribute(dbc::SignalAttribute { ref id, .. }) => id,
+            Entry::ValueDescription(dbc::ValueDescription { ref id, .. }) => id,
+            Entry::Unknown(ref line) if line.starts_with("BO_ ") => {
+                // Malformed message definition, whose `SG_` entries mustn't attach to the
+                // previous message
+                self.last_id = None;
+                return Err(LoadError::new(LoadErrorKind::Unparseable, Some(entry)));
+            }
             _ => {
-                return Err(format!("Unsupported entry: {entry}."));
+                let kind = LoadErrorKind::UnsupportedEntry(entry.get_type());
+                return Err(LoadError::new(kind, Some(entry)));
             }
         };
 
-        self.messages
-            .entry(_id)
-            .and_modify(|cur_entry| {
-                cur_entry
-                    .merge_entry(entry.clone())
-                    .unwrap_or_else(|_| panic!("Already checked for Signal key: {:?}", entry))
-            })
-            .or_insert_with(|| {
-                Message::from_entry(entry.clone())
-                    .unwrap_or_else(|_| panic!("Some inserted a Signal for empty key: {:?}", _id))
-            });
+        // `VAL_` refines an existing `SG_` definition
+        if let Entry::ValueDescription(dbc::ValueDescription {
+            ref signal_name, ..
+        }) = entry
+        {
+            let defined = self
+                .messages
+                .get(&_id)
+                .is_some_and(|message| message.signals.contains_key(signal_name));
+            if !defined {
+                return Err(LoadError::new(
+                    LoadErrorKind::MissingDefinition,
+                    Some(entry),
+                ));
+            }
+        }
+
+        // Signals whose labels match a named table share it rather than holding their own copy.
+        // `VAL_` has no reference to a table, so pick the lowest name for a deterministic match.
+        let value_table = match entry {
+            Entry::ValueDescription(dbc::ValueDescription {
+                ref signal_name,
+                ref values,
+                ..
+            }) => self
+                .value_tables
+                .iter()
+                .filter(|(_name, table)| table.as_ref() == values)
+                .min_by(|(a, _), (b, _)| a.cmp(b))
+                .map(|(name, table)| (signal_name.clone(), name.clone(), Arc::clone(table))),
+            _ => None,
+        };
+
+        let is_message_definition = matches!(entry, Entry::MessageDefinition(..));
+        match self.messages.get_mut(&_id) {
+            Some(message) => message.merge_entry(entry.clone()),
+            None => Message::from_entry(entry.clone()).map(|message| {
+                self.messages.insert(_id, message);
+            }),
+        }
+        .map_err(|_| invalid_entry("Failed to add message entry.", entry))?;
+
+        if let Some((signal_name, table_name, table)) = value_table {
+            if let Some(signal) = self
+                .messages
+                .get_mut(&_id)
+                .and_then(|message| message.signals.get_mut(&signal_name))
+            {
+                signal.value_definition = Some(table);
+                signal.value_table = Some(table_name);
+            }
+        }
 
-        self.last_id = Some(_id);
+        if is_message_definition {
+            self.last_id = Some(_id);
+        }
         Ok(())
     }
+
+    /// Checks an attribute value against its `BA_DEF_` definition, and converts it to the
+    /// definition's type.  Attributes without a definition are accepted as-is, and defaults
+    /// (`object_type` of `None`) apply to any type of object.
+    fn resolve_attribute(
+        &self,
+        object_type: Option<AttributeObjectType>,
+        name: &str,
+        value: &mut dbc::AttributeValue,
+    ) -> Result<(), String> {
+        let definition = match self.attribute_definitions.get(name) {
+            Some(definition) => definition,
+            None => return Ok(()),
+        };
+
+        match object_type {
+            Some(object_type) if definition.object_type != object_type => Err(format!(
+                "Attribute {name} is defined for {:?}, not {object_type:?}.",
+                definition.object_type
+            )),
+            _ => match definition.value_type.resolve(value) {
+                Some(resolved) => {
+                    *value = resolved;
+                    Ok(())
+                }
+                None => Err(format!(
+                    "Attribute {name} value {value} does not match {:?}.",
+                    definition.value_type
+                )),
+            },
+        }
+    }
+
+    /// Returns the default (`BA_DEF_DEF_`) for an attribute, if the attribute is defined
+    /// (`BA_DEF_`) for the given type of object.
+    fn attribute_default(
+        &self,
+        object_type: AttributeObjectType,
+        name: &str,
+    ) -> Option<&dbc::AttributeValue> {
+        match self.attribute_definitions.get(name) {
+            Some(definition) if definition.object_type == object_type => {
+                self.attribute_defaults.get(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the default of a relation attribute (`BA_DEF_DEF_REL_`), if any.
+    pub fn relation_attribute_default(&self, name: &str) -> Option<&dbc::AttributeValue> {
+        self.relation_attribute_defaults.get(name)
+    }
+
+    /// Returns the database comment (`CM_ "..."`), if any.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the value of a network attribute (e.g., `BusType`, `ProtocolType` or `DBName`),
+    /// falling back to the attribute default if the database has no explicit value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use canparse::dbc::DbcLibrary;
+    ///
+    /// let lib: DbcLibrary = DbcLibrary::from_dbc_file("./tests/data/sample.dbc").unwrap();
+    ///
+    /// let protocol = lib.attribute("ProtocolType");
+    /// assert_eq!(protocol.and_then(|value| value.as_str()), Some("J1939"));
+    /// ```
+    pub fn attribute(&self, name: &str) -> Option<&dbc::AttributeValue> {
+        self.attributes
+            .get(name)
+            .or_else(|| self.attribute_default(AttributeObjectType::Network, name))
+    }
+
+    /// Returns the value of an environment variable attribute, falling back to the attribute
+    /// default if the variable has no explicit value.
+    pub fn environment_variable_attribute(
+        &self,
+        env_var_name: &str,
+        name: &str,
+    ) -> Option<&dbc::AttributeValue> {
+        let env_var = self.environment_variables.get(env_var_name)?;
+        env_var
+            .attribute(name)
+            .or_else(|| self.attribute_default(AttributeObjectType::EnvironmentVariable, name))
+    }
+
+    /// Returns the value of a node attribute, falling back to the attribute default if the
+    /// node has no explicit value.
+    pub fn node_attribute(&self, node_name: &str, name: &str) -> Option<&dbc::AttributeValue> {
+        let node = self.nodes.get(node_name)?;
+        node.attribute(name)
+            .or_else(|| self.attribute_default(AttributeObjectType::Node, name))
+    }
+
+    /// Returns the value of a message attribute, falling back to the attribute default if the
+    /// message has no explicit value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use canparse::dbc::DbcLibrary;
+    ///
+    /// let lib: DbcLibrary = DbcLibrary::from_dbc_file("./tests/data/sample.dbc").unwrap();
+    ///
+    /// let cycle_time = lib.message_attribute(2364539904, "GenMsgCycleTime");
+    /// assert_eq!(cycle_time.and_then(|value| value.as_i64()), Some(100));
+    /// ```
+    pub fn message_attribute(&self, id: u32, name: &str) -> Option<&dbc::AttributeValue> {
+        let message = self.messages.get(&id)?;
+        message
+            .attribute(name)
+            .or_else(|| self.attribute_default(AttributeObjectType::Message, name))
+    }
+
+    /// Returns the value of a signal attribute, falling back to the attribute default if the
+    /// signal has no explicit value.
+    pub fn signal_attribute(
+        &self,
+        id: u32,
+        signal_name: &str,
+        name: &str,
+    ) -> Option<&dbc::AttributeValue> {
+        let signal = self.messages.get(&id)?.signals.get(signal_name)?;
+        signal
+            .attribute(name)
+            .or_else(|| self.attribute_default(AttributeObjectType::Signal, name))
+    }
+
+    /// Returns an attribute definition (`BA_DEF_`) reference, if it exists.
+    pub fn get_attribute_definition(&self, name: &str) -> Option<&dbc::AttributeDefinition> {
+        self.attribute_definitions.get(name)
+    }
+
+    /// Returns an `EnvironmentVariable` entry reference, if it exists.
+    pub fn get_environment_variable(&self, name: &str) -> Option<&EnvironmentVariable> {
+        self.environment_variables.get(name)
+    }
+
+    /// Returns a `Node` entry reference, if it exists.
+    pub fn get_node(&self, name: &str) -> Option<&Node> {
+        self.nodes.get(name)
+    }
+
+    /// Iterates over all nodes (`BU_`) defined in the library.
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.values()
+    }
+
+    /// Returns the `Node` which transmits the message with the given ID, if both exist.
+    pub fn get_transmitter(&self, id: u32) -> Option<&Node> {
+        self.messages
+            .get(&id)
+            .and_then(|message| self.nodes.get(&message.sending_node))
+    }
+
+    /// Returns a named `VAL_TABLE_` value definition, if it exists.
+    pub fn get_value_table(&self, name: &str) -> Option<&dbc::ValueDefinition> {
+        self.value_tables.get(name).map(|table| table.as_ref())
+    }
+
+    /// Returns a `Message` entry reference, if it exists.
+    pub fn get_message(&self, id: u32) -> Option<&Message> {
+        self.messages.get(&id)
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::DbcLibrary;
-    use crate::dbc::{Entry, SignalDefinition, Version};
+    use super::{DbcLibrary, Signal};
+    use crate::dbc::{
+        AttributeType, AttributeValue, Entry, LoadErrorKind, MultiplexIndicator, SignalDefinition,
+        ValueDefinition, ValueType, Version,
+    };
+    use crate::pgn::{
+        CheckedValue, EncodeMessage, ParseMessage, ParseMessageAs, ParseMessageChecked,
+        ParseMessageRaw,
+    };
+    use std::str::FromStr;
 
     lazy_static! {
         static ref DBCLIB_EMPTY: DbcLibrary = DbcLibrary::default();
@@ -384,10 +1124,12 @@ mod tests {
             .expect("Failed to create DbcLibrary from file");
         static ref SIGNALDEF: SignalDefinition = SignalDefinition {
             name: "Engine_Speed".to_string(),
+            multiplex_indicator: MultiplexIndicator::Plain,
             start_bit: 24,
             bit_len: 16,
             little_endian: true,
             signed: false,
+            value_type: ValueType::Integer,
             scale: 0.125,
             offset: 0.0,
             min_value: 0.0,
@@ -426,6 +1168,462 @@ mod tests {
         );
     }
 
+    #[test]
+    fn multiplexed_signals() {
+        let message = DBCLIB_ONE
+            .get_message(2566848512)
+            .expect("failed to get Message from DbcLibrary");
+        assert_eq!(
+            message
+                .multiplexor()
+                .and_then(Signal::definition)
+                .map(|def| def.name.as_str()),
+            Some("PropDiag_Page")
+        );
+
+        let page2: [u8; 8] = [0x02, 0xC8, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert_eq!(message.active_signals(&page2).len(), 2);
+
+        let values = message.parse_message(&page2);
+        assert_eq!(values.get("PropDiag_Page"), Some(&2.0));
+        assert!(values.contains_key("PropDiag_FuelRate"));
+        assert!(!values.contains_key("PropDiag_OilTemp"));
+
+        // Page 5 selects the nested status multiplexor, whose value 0x04 is within 4-5
+        let fault_code = message
+            .get_signal("PropDiag_FaultCode")
+            .expect("failed to get Signal from Message");
+        assert_eq!(
+            fault_code.multiplex_selector().map(|s| s.ranges.clone()),
+            Some(vec![(4, 5)])
+        );
+        let page5: [u8; 8] = [0x05, 0x04, 0x0A, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert_eq!(
+            message.parse_message(&page5).get("PropDiag_FaultCode"),
+            Some(&10.0)
+        );
+        let page5_status6: [u8; 8] = [0x05, 0x06, 0x0A, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert!(!message
+            .parse_message(&page5_status6)
+            .contains_key("PropDiag_FaultCode"));
+
+        let mut dbclib: DbcLibrary = DbcLibrary::default();
+        dbclib
+            .add_entry(Entry::from_str("BO_ 2566848512 PropDiag : 8 Engine\n").unwrap())
+            .unwrap();
+        let orphan =
+            Entry::from_str("SG_MUL_VAL_ 2566848512 PropDiag_Missing PropDiag_Page 1-1;\n")
+                .unwrap();
+        assert_eq!(
+            dbclib.add_entry(orphan).map_err(|e| e.kind().clone()),
+            Err(LoadErrorKind::MissingDefinition),
+            "SG_MUL_VAL_ without a signal definition"
+        );
+        assert!(dbclib
+            .get_message(2566848512)
+            .is_some_and(|message| message.get_signal("PropDiag_Missing").is_none()));
+    }
+
+    #[test]
+    fn float_signals() {
+        let message = DBCLIB_ONE
+            .get_message(2566848768)
+            .expect("failed to get Message from DbcLibrary");
+        let density = message
+            .get_signal("PropFuel_Density")
+            .and_then(Signal::definition)
+            .expect("failed to get SignalDefinition from Message");
+        assert_eq!(density.value_type, ValueType::Float32);
+
+        let mut msg = [0xFFu8; 8];
+        msg[..4].copy_from_slice(&835.5f32.to_le_bytes());
+        assert_eq!(
+            message.parse_message(&msg).get("PropFuel_Density"),
+            Some(&835.5)
+        );
+
+        let mut dbclib: DbcLibrary = DbcLibrary::default();
+        let orphan = Entry::from_str("SIG_VALTYPE_ 2566848768 PropFuel_Density : 1;\n").unwrap();
+        assert!(
+            dbclib.add_entry(orphan).is_err(),
+            "SIG_VALTYPE_ without a signal definition"
+        );
+    }
+
+    #[test]
+    fn parse_signal_checked() {
+        let msg: [u8; 8] = [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88];
+        assert_eq!(
+            SIGNALDEF.parse_message_checked(&msg),
+            Some(CheckedValue::InRange(2728.5))
+        );
+
+        let msg: [u8; 8] = [0x11, 0x22, 0x33, 0xF0, 0xFF, 0x66, 0x77, 0x88];
+        assert_eq!(
+            SIGNALDEF.parse_message_checked(&msg[..]),
+            Some(CheckedValue::OutOfRange(8190.0))
+        );
+    }
+
+    #[test]
+    fn encode_signal() {
+        let mut msg = [0xFFu8; 8];
+        SIGNALDEF.encode_message(2728.5, &mut msg).unwrap();
+        assert_eq!(msg, [0xFF, 0xFF, 0xFF, 0x44, 0x55, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(SIGNALDEF.parse_message(&msg[..]), Some(2728.5));
+        assert_eq!(SIGNALDEF.parse_raw_u64(&msg[..]), Some(0x5544));
+        assert_eq!(SIGNALDEF.parse_message_as::<f64>(&msg[..]), Some(2728.5));
+    }
+
+    #[test]
+    fn get_value_definition() {
+        let values = DBCLIB_ONE
+            .get_message(2364539904)
+            .and_then(|message| message.get_signal("Engine_Starter_Mode"))
+            .and_then(|signal| signal.value_definition())
+            .expect("failed to get ValueDefinition from DbcLibrary");
+
+        assert_eq!(values.len(), 3);
+        assert_eq!(values.get(15), Some("NotAvailable"));
+        assert_eq!(values.get(1), None);
+    }
+
+    #[test]
+    fn get_value_table() {
+        let table = DBCLIB_ONE
+            .get_value_table("StarterMode")
+            .expect("failed to get value table from DbcLibrary");
+        let signal = DBCLIB_ONE
+            .get_message(2364539904)
+            .and_then(|message| message.get_signal("Engine_Starter_Mode"))
+            .expect("failed to get Signal from DbcLibrary");
+
+        assert_eq!(signal.value_table(), Some("StarterMode"));
+        assert!(std::ptr::eq(
+            signal.value_definition().unwrap(),
+            table as *const ValueDefinition
+        ));
+        assert!(DBCLIB_ONE.get_value_table("Missing").is_none());
+    }
+
+    #[test]
+    fn ambiguous_value_table() {
+        let mut dbclib: DbcLibrary = DbcLibrary::default();
+        for line in &[
+            "VAL_TABLE_ Switch_B 1 \"On\" 0 \"Off\" ;\n",
+            "VAL_TABLE_ Switch_C 1 \"On\" 0 \"Off\" ;\n",
+            "VAL_TABLE_ Switch_A 1 \"On\" 0 \"Off\" ;\n",
+            "BO_ 2364539904 EEC1 : 8 Vector__XXX\n",
+            " SG_ Engine_Brake : 0|2@1+ (1,0) [0|3] \"\" Vector__XXX\n",
+            "VAL_ 2364539904 Engine_Brake 1 \"On\" 0 \"Off\" ;\n",
+        ] {
+            dbclib
+                .add_entry(Entry::from_str(line).unwrap())
+                .unwrap_or_else(|e| panic!("failed to add {:?}: {}", line, e));
+        }
+
+        let signal = dbclib
+            .get_message(2364539904)
+            .and_then(|message| message.get_signal("Engine_Brake"))
+            .unwrap();
+        assert_eq!(signal.value_table(), Some("Switch_A"));
+
+        let orphan = Entry::from_str("VAL_ 2364539904 Engine_Retarder 1 \"On\" 0 \"Off\" ;\n");
+        assert_eq!(
+            dbclib
+                .add_entry(orphan.unwrap())
+                .map_err(|e| e.kind().clone()),
+            Err(LoadErrorKind::MissingDefinition),
+            "VAL_ without a signal definition"
+        );
+        assert!(dbclib
+            .get_message(2364539904)
+            .is_some_and(|message| message.get_signal("Engine_Retarder").is_none()));
+    }
+
+    #[test]
+    fn get_node() {
+        let node = DBCLIB_ONE
+            .get_node("Engine")
+            .expect("failed to get Node from DbcLibrary");
+
+        assert_eq!(node.name(), "Engine");
+        assert_eq!(node.description(), Some("Engine control module"));
+        assert_eq!(
+            node.attribute("NmStationAddress"),
+            Some(&AttributeValue::Hex(0))
+        );
+        assert!(node.attribute("Missing").is_none());
+        assert_eq!(DBCLIB_ONE.nodes().count(), 2);
+        assert_eq!(
+            DBCLIB_ONE
+                .get_transmitter(2364539904)
+                .map(|node| node.name()),
+            Some("Engine")
+        );
+    }
+
+    #[test]
+    fn attribute_defaults() {
+        assert_eq!(
+            DBCLIB_ONE.message_attribute(2364539904, "GenMsgCycleTime"),
+            Some(&AttributeValue::Int(100))
+        );
+        assert_eq!(
+            DBCLIB_ONE.message_attribute(2364539904, "SingleFrame"),
+            Some(&AttributeValue::Int(0))
+        );
+        assert_eq!(
+            DBCLIB_ONE.signal_attribute(2364539904, "Engine_Speed", "SPN"),
+            Some(&AttributeValue::Int(190))
+        );
+        assert_eq!(
+            DBCLIB_ONE.signal_attribute(2364539904, "Engine_Starter_Mode", "SPN"),
+            Some(&AttributeValue::Int(0))
+        );
+        assert_eq!(
+            DBCLIB_ONE.node_attribute("Transmission", "NmStationAddress"),
+            Some(&AttributeValue::Hex(254))
+        );
+
+        // Defaults only apply to the object type of their definition
+        assert!(DBCLIB_ONE.message_attribute(2364539904, "SPN").is_none());
+        // ...and only to defined attributes
+        assert!(DBCLIB_ONE
+            .message_attribute(2364539904, "Undefined")
+            .is_none());
+        // ...and only to objects which exist
+        assert!(DBCLIB_ONE.message_attribute(0, "GenMsgCycleTime").is_none());
+    }
+
+    #[test]
+    fn relation_attribute_defaults() {
+        let mut dbclib: DbcLibrary = DbcLibrary::default();
+        for line in &[
+            "BA_DEF_ SG_ \"GenSigTimeoutTime\" INT 0 65535;\n",
+            "BA_DEF_DEF_ \"GenSigTimeoutTime\" 100;\n",
+            "BA_DEF_DEF_REL_ \"GenSigTimeoutTime\" 3;\n",
+            "BA_DEF_DEF_REL_ \"NodeLayerModules\" \"\";\n",
+            "BO_ 2364539904 EEC1 : 8 Vector__XXX\n",
+            " SG_ Engine_Speed : 24|16@1+ (0.125,0) [0|8031.88] \"rpm\" Vector__XXX\n",
+        ] {
+            dbclib
+                .add_entry(Entry::from_str(line).unwrap())
+                .unwrap_or_else(|e| panic!("failed to add {:?}: {}", line, e));
+        }
+
+        // The relation default doesn't replace the plain one
+        assert_eq!(
+            dbclib.signal_attribute(2364539904, "Engine_Speed", "GenSigTimeoutTime"),
+            Some(&AttributeValue::Int(100))
+        );
+        assert_eq!(
+            dbclib.relation_attribute_default("GenSigTimeoutTime"),
+            Some(&AttributeValue::Int(3))
+        );
+
+        // ...nor applies to any object
+        assert!(dbclib
+            .message_attribute(2364539904, "NodeLayerModules")
+            .is_none());
+        assert!(dbclib
+            .signal_attribute(2364539904, "Engine_Speed", "NodeLayerModules")
+            .is_none());
+        assert_eq!(
+            dbclib.relation_attribute_default("NodeLayerModules"),
+            Some(&AttributeValue::String("".to_string()))
+        );
+    }
+
+    #[test]
+    fn network_attributes() {
+        assert_eq!(DBCLIB_ONE.description(), Some("J1939 sample database"));
+        assert_eq!(
+            DBCLIB_ONE.attribute("BusType"),
+            Some(&AttributeValue::String("CAN".to_string()))
+        );
+        assert_eq!(
+            DBCLIB_ONE.attribute("ProtocolType"),
+            Some(&AttributeValue::String("J1939".to_string()))
+        );
+        assert_eq!(
+            DBCLIB_ONE.attribute("DBName"),
+            Some(&AttributeValue::String("sample".to_string()))
+        );
+        assert!(DBCLIB_ONE.attribute("GenMsgCycleTime").is_none());
+    }
+
+    #[test]
+    fn environment_variables() {
+        let mut dbclib: DbcLibrary = DbcLibrary::default();
+        for line in &[
+            "BA_DEF_ EV_ \"EnvStartValue\" INT 0 3;\n",
+            "BA_DEF_DEF_ \"EnvStartValue\" 0;\n",
+            "CM_ EV_ IgnitionSwitch \"Ignition key position\";\n",
+            "BA_ \"EnvStartValue\" EV_ IgnitionSwitch 2;\n",
+            "BA_ \"EnvStartValue\" EV_ Headlights 0;\n",
+        ] {
+            dbclib
+                .add_entry(Entry::from_str(line).unwrap())
+                .unwrap_or_else(|e| panic!("failed to add {:?}: {}", line, e));
+        }
+
+        let env_var = dbclib
+            .get_environment_variable("IgnitionSwitch")
+            .expect("failed to get EnvironmentVariable from DbcLibrary");
+        assert_eq!(env_var.description(), Some("Ignition key position"));
+        assert_eq!(
+            dbclib.environment_variable_attribute("IgnitionSwitch", "EnvStartValue"),
+            Some(&AttributeValue::Int(2))
+        );
+        assert!(dbclib.get_environment_variable("Headlights").is_some());
+
+        let out_of_range = "BA_ \"EnvStartValue\" EV_ IgnitionSwitch 4;\n";
+        assert!(dbclib
+            .add_entry(Entry::from_str(out_of_range).unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn validate_attribute() {
+        let mut dbclib: DbcLibrary = DbcLibrary::default();
+        let definition = "BA_DEF_ BO_ \"GenMsgCycleTime\" INT 0 1000;\n";
+        dbclib
+            .add_entry(Entry::from_str(definition).unwrap())
+            .expect("failed to add attribute definition");
+        assert_eq!(
+            dbclib
+                .get_attribute_definition("GenMsgCycleTime")
+                .map(|definition| &definition.value_type),
+            Some(&AttributeType::Int { min: 0, max: 1000 })
+        );
+
+        let valid = "BA_ \"GenMsgCycleTime\" BO_ 2364539904 100;\n";
+        assert!(dbclib.add_entry(Entry::from_str(valid).unwrap()).is_ok());
+
+        let out_of_range = "BA_ \"GenMsgCycleTime\" BO_ 2364539904 5000;\n";
+        assert!(dbclib
+            .add_entry(Entry::from_str(out_of_range).unwrap())
+            .is_err());
+
+        let wrong_object = "BA_ \"GenMsgCycleTime\" SG_ 2364539904 Engine_Speed 100;\n";
+        assert!(dbclib
+            .add_entry(Entry::from_str(wrong_object).unwrap())
+            .is_err());
+
+        let undefined = "BA_ \"SPN\" SG_ 2364539904 Engine_Speed 190;\n";
+        assert!(dbclib
+            .add_entry(Entry::from_str(undefined).unwrap())
+            .is_ok());
+    }
+
+    #[test]
+    fn typed_attributes() {
+        let mut dbclib: DbcLibrary = DbcLibrary::default();
+        for line in &[
+            "BA_DEF_ BO_ \"VFrameFormat\" ENUM \"StandardCAN\",\"ExtendedCAN\",\"J1939PG\";\n",
+            "BA_DEF_ BO_ \"GenMsgStartDelay\" FLOAT -10 10;\n",
+            "BA_DEF_ SG_ \"SigComment\" STRING ;\n",
+            "BA_DEF_DEF_ \"VFrameFormat\" \"J1939PG\";\n",
+            "BO_ 2364539904 EEC1 : 8 Vector__XXX\n",
+            " SG_ Engine_Speed : 24|16@1+ (0.125,0) [0|8031.88] \"rpm\" Vector__XXX\n",
+            "BA_ \"GenMsgStartDelay\" BO_ 2364539904 -2.5;\n",
+            "BA_ \"SigComment\" SG_ 2364539904 Engine_Speed \"Crankshaft speed\";\n",
+        ] {
+            dbclib
+                .add_entry(Entry::from_str(line).unwrap())
+                .unwrap_or_else(|e| panic!("failed to add {:?}: {}", line, e));
+        }
+
+        assert_eq!(
+            dbclib.message_attribute(2364539904, "VFrameFormat"),
+            Some(&AttributeValue::Enum(2, "J1939PG".to_string()))
+        );
+
+        let message = dbclib.get_message(2364539904).unwrap();
+        assert_eq!(message.attribute_f64("GenMsgStartDelay"), Some(-2.5));
+        assert_eq!(message.attribute_i64("GenMsgStartDelay"), None);
+
+        let signal = message.get_signal("Engine_Speed").unwrap();
+        assert_eq!(signal.attribute_str("SigComment"), Some("Crankshaft speed"));
+
+        let wrong_label = "BA_ \"VFrameFormat\" BO_ 2364539904 \"Unknown\";\n";
+        assert!(dbclib
+            .add_entry(Entry::from_str(wrong_label).unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn load_malformed_dbc() {
+        let (dbclib, warnings) = DbcLibrary::load_dbc_str(concat!(
+            "BA_DEF_ BO_ \"GenMsgCycleTime\" INT 0 1000;\n",
+            " SG_ Orphan : 0|8@1+ (1,0) [0|255] \"\" Vector__XXX\n",
+            "BO_ 2364539904 EEC1 : 8 Vector__XXX\n",
+            " SG_ Engine_Speed : 24|16@1+ (0.125,0) [0|8031.88] \"rpm\" Vector__XXX\n",
+            "\u{e9}\r\u{e9}\n",
+            "BA_ \"GenMsgCycleTime\" BO_ 2364539904 5000;\n",
+            "SIG_VALTYPE_ 2566848768 PropFuel_Density : 1;\n",
+        ));
+
+        assert!(dbclib
+            .get_message(2364539904)
+            .and_then(|message| message.get_signal("Engine_Speed"))
+            .is_some());
+        assert_eq!(
+            dbclib.message_attribute(2364539904, "GenMsgCycleTime"),
+            None
+        );
+
+        let found: Vec<_> = warnings
+            .iter()
+            .map(|warning| (warning.line(), warning.kind().clone()))
+            .collect();
+        assert_eq!(found.len(), 4);
+        assert_eq!(found[0], (Some(2), LoadErrorKind::MissingDefinition));
+        assert_eq!(found[1], (Some(5), LoadErrorKind::Unparseable));
+        assert_eq!(found[2].0, Some(6));
+        assert!(matches!(found[2].1, LoadErrorKind::InvalidEntry(_)));
+        assert_eq!(found[3], (Some(7), LoadErrorKind::MissingDefinition));
+        assert_eq!(
+            warnings[2].source_line(),
+            Some("BA_ \"GenMsgCycleTime\" BO_ 2364539904 5000;")
+        );
+    }
+
+    #[test]
+    fn load_signals_after_bad_message() {
+        let (dbclib, warnings) = DbcLibrary::load_dbc_str(concat!(
+            "BO_ 2364539904 EEC1 : 8 Vector__XXX\n",
+            " SG_ Engine_Speed : 24|16@1+ (0.125,0) [0|8031.88] \"rpm\" Vector__XXX\n",
+            "CM_ BO_ 2566848512 \"Proprietary diagnostics\";\n",
+            " SG_ Engine_Starter_Mode : 48|4@1+ (1,0) [0|15] \"\" Vector__XXX\n",
+            "BO_ 2566848768 PropFuel : x Vector__XXX\n",
+            " SG_ PropFuel_Density : 0|32@1- (1,0) [0|0] \"kg/m3\" Vector__XXX\n",
+        ));
+
+        // Comments don't change the message which following signals belong to
+        let eec1 = dbclib.get_message(2364539904).unwrap();
+        assert!(eec1.get_signal("Engine_Starter_Mode").is_some());
+        assert!(dbclib
+            .get_message(2566848512)
+            .is_some_and(|message| message.get_signal("Engine_Starter_Mode").is_none()));
+
+        // Signals of a message which failed to load aren't attached to the previous message
+        assert!(eec1.get_signal("PropFuel_Density").is_none());
+        assert!(dbclib.get_message(2566848768).is_none());
+        let found: Vec<_> = warnings
+            .iter()
+            .map(|warning| (warning.line(), warning.kind().clone()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Some(5), LoadErrorKind::Unparseable),
+                (Some(6), LoadErrorKind::MissingDefinition),
+            ]
+        );
+    }
+
     #[test]
     fn unsupported_entry() {
         let mut dbclib: DbcLibrary = DbcLibrary::default();
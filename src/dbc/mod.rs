@@ -2,6 +2,8 @@
 
 #![allow(non_upper_case_globals)]
 
+use crate::signal::{Layout, SignalLayout};
+use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt;
 use std::fmt::{Display, Formatter};
@@ -18,6 +20,44 @@ pub struct Version(pub String);
 #[derive(Debug, Clone, PartialEq)]
 pub struct BusConfiguration(pub f32);
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NetworkDescription(pub String);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkAttribute {
+    pub name: String,
+    pub value: AttributeValue,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CanNodes(pub Vec<String>);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CanNodesDescription {
+    pub node_name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanNodesAttribute {
+    pub name: String,
+    pub node_name: String,
+    pub value: AttributeValue,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EnvironmentVariableDescription {
+    pub env_var_name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvironmentVariableAttribute {
+    pub name: String,
+    pub env_var_name: String,
+    pub value: AttributeValue,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct MessageDefinition {
     pub id: u32,
@@ -34,21 +74,108 @@ pub struct MessageDescription {
     pub description: String,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MessageAttribute {
     pub name: String,
     pub id: u32,
     pub signal_name: String,
-    pub value: String,
+    pub value: AttributeValue,
+}
+
+/// Multiplexing role of a signal within its message, e.g., the `M` or `m3` in
+/// `SG_ Name m3 : ...`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MultiplexIndicator {
+    /// Signal is present in every frame of the message
+    #[default]
+    Plain,
+    /// Signal selects which multiplexed signals are present (`M`)
+    Multiplexor,
+    /// Signal is only present when the multiplexor's raw value matches (`mN`)
+    Multiplexed(u64),
+    /// Multiplexed signal which is itself a multiplexor for nested signals (`mNM`)
+    MultiplexedMultiplexor(u64),
+}
+
+impl MultiplexIndicator {
+    /// Returns whether a signal with this indicator is present in a frame, given the frame's
+    /// multiplexor value (`None` if the message has no multiplexor).
+    pub fn is_active(&self, multiplexor_value: Option<u64>) -> bool {
+        match self.multiplexed_value() {
+            Some(value) => multiplexor_value == Some(value),
+            None => true,
+        }
+    }
+
+    /// Returns the multiplexor value selecting the signal, if it is multiplexed (`mN`/`mNM`).
+    pub fn multiplexed_value(&self) -> Option<u64> {
+        match *self {
+            MultiplexIndicator::Multiplexed(value)
+            | MultiplexIndicator::MultiplexedMultiplexor(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the signal selects other multiplexed signals (`M`/`mNM`).
+    pub fn is_multiplexor(&self) -> bool {
+        matches!(
+            *self,
+            MultiplexIndicator::Multiplexor | MultiplexIndicator::MultiplexedMultiplexor(_)
+        )
+    }
+}
+
+/// Multiplexor switch and raw value ranges selecting a multiplexed signal, e.g., the
+/// `Mux 1-1, 4-5` in `SG_MUL_VAL_ 2566848512 Signal Mux 1-1, 4-5;`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MultiplexSelector {
+    pub multiplexor_name: String,
+    /// Inclusive `(low, high)` raw value ranges
+    pub ranges: Vec<(u64, u64)>,
+}
+
+impl MultiplexSelector {
+    /// Returns whether the multiplexor's raw value falls within any of the selector's ranges.
+    pub fn contains(&self, multiplexor_value: u64) -> bool {
+        self.ranges
+            .iter()
+            .any(|&(low, high)| low <= multiplexor_value && multiplexor_value <= high)
+    }
+}
+
+/// Encoding of a signal's raw bits, e.g., the `1` in `SIG_VALTYPE_ 2364539904 Signal : 1;`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValueType {
+    /// Signed or unsigned integer, as given by the signal definition (`SG_`)
+    #[default]
+    Integer,
+    /// IEEE 754 single precision float (`1`)
+    Float32,
+    /// IEEE 754 double precision float (`2`)
+    Float64,
+}
+
+impl ValueType {
+    /// Returns the `ValueType` for a `SIG_VALTYPE_` code, if it is valid.
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(ValueType::Integer),
+            1 => Some(ValueType::Float32),
+            2 => Some(ValueType::Float64),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct SignalDefinition {
     pub name: String,
+    pub multiplex_indicator: MultiplexIndicator,
     pub start_bit: usize,
     pub bit_len: usize,
     pub little_endian: bool,
     pub signed: bool,
+    pub value_type: ValueType,
     pub scale: f32,
     pub offset: f32,
     pub min_value: f32,
@@ -57,6 +184,22 @@ pub struct SignalDefinition {
     pub receiving_node: String,
 }
 
+impl Layout for SignalDefinition {
+    fn layout(&self) -> SignalLayout {
+        SignalLayout {
+            start_bit: self.start_bit,
+            bit_len: self.bit_len,
+            little_endian: self.little_endian,
+            signed: self.signed,
+            value_type: self.value_type,
+            scale: self.scale,
+            offset: self.offset,
+            min_value: self.min_value,
+            max_value: self.max_value,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SignalDescription {
     pub id: u32,
@@ -64,12 +207,52 @@ pub struct SignalDescription {
     pub description: String,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SignalAttribute {
     pub name: String,
     pub id: u32,
     pub signal_name: String,
-    pub value: String,
+    pub value: AttributeValue,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ValueTable {
+    pub name: String,
+    pub values: ValueDefinition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeDefinition {
+    pub object_type: AttributeObjectType,
+    pub name: String,
+    pub value_type: AttributeType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeDefault {
+    pub name: String,
+    pub value: AttributeValue,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ValueDescription {
+    pub id: u32,
+    pub signal_name: String,
+    pub values: ValueDefinition,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SignalValueType {
+    pub id: u32,
+    pub signal_name: String,
+    pub value_type: ValueType,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ExtendedMultiplexing {
+    pub id: u32,
+    pub signal_name: String,
+    pub selector: MultiplexSelector,
 }
 
 /// Composed DBC entry.
@@ -81,11 +264,26 @@ pub enum Entry {
     /// BS_: <Speed>
     BusConfiguration(BusConfiguration),
 
-    // TODO: ??
-    // CanNodes,
-    // `CM_ BU_ [can id] [signal name] "[description]"`
-    // CanNodesDescription,
-    // CanNodesAttribute,
+    /// `CM_ "[description]";`
+    NetworkDescription(NetworkDescription),
+    /// `BA_ "[attribute name]" [attribute value];`
+    NetworkAttribute(NetworkAttribute),
+
+    /// `VAL_TABLE_ [table name] [[raw value] "[label]"]... ;`
+    ValueTable(ValueTable),
+
+    /// `BU_: [node name]...`
+    CanNodes(CanNodes),
+    /// `CM_ BU_ [node name] "[description]"`
+    CanNodesDescription(CanNodesDescription),
+    /// `BA_ "[attribute name]" BU_ [node name] [attribute value];`
+    CanNodesAttribute(CanNodesAttribute),
+
+    /// `CM_ EV_ [variable name] "[description]";`
+    EnvironmentVariableDescription(EnvironmentVariableDescription),
+    /// `BA_ "[attribute name]" EV_ [variable name] [attribute value];`
+    EnvironmentVariableAttribute(EnvironmentVariableAttribute),
+
     /// `BO_ [can id] [message name]: [message length] [sending node]`
     MessageDefinition(MessageDefinition),
     /// `CM_ BO_ [can id] [signal name] "[description]"`
@@ -99,15 +297,22 @@ pub enum Entry {
     SignalDescription(SignalDescription),
     /// `BA_ "[attribute name]" SG_ [node|can id] [signal name] [attribute value];`
     SignalAttribute(SignalAttribute),
+    /// `VAL_ [can id] [signal name] [[raw value] "[label]"]... ;`
+    ValueDescription(ValueDescription),
+    /// `SIG_VALTYPE_ [can id] [signal name] : [value type];`
+    SignalValueType(SignalValueType),
+    /// `SG_MUL_VAL_ [can id] [signal name] [multiplexor name] [[low]-[high]], ... ;`
+    ExtendedMultiplexing(ExtendedMultiplexing),
 
     // `CM_ [BU_|BO_|SG_] [can id] [signal name] "[description]"`
     // Description, -- flatten subtypes instead
+    /// `BA_DEF_ [BU_|BO_|SG_|EV_] "[attribute name]" [attribute type];`
+    AttributeDefinition(AttributeDefinition),
 
-    // `BA_DEF_ ...`
-    // AttributeDefinition,
-
-    // `BA_DEF_DEF_ ...`
-    // AttributeDefault,
+    /// `BA_DEF_DEF_ "[attribute name]" [default value];`
+    AttributeDefault(AttributeDefault),
+    /// `BA_DEF_DEF_REL_ "[attribute name]" [default value];`
+    RelationAttributeDefault(AttributeDefault),
 
     // `BA_ "[attribute name]" [BU_|BO_|SG_] [node|can id] [signal name] [attribute value];`
     // Attribute
@@ -121,12 +326,26 @@ impl Entry {
         match self {
             Entry::Version(_) => EntryType::Version,
             Entry::BusConfiguration(_) => EntryType::BusConfiguration,
+            Entry::NetworkDescription(_) => EntryType::NetworkDescription,
+            Entry::NetworkAttribute(_) => EntryType::NetworkAttribute,
+            Entry::ValueTable(_) => EntryType::ValueTable,
+            Entry::CanNodes(_) => EntryType::CanNodes,
+            Entry::CanNodesDescription(_) => EntryType::CanNodesDescription,
+            Entry::CanNodesAttribute(_) => EntryType::CanNodesAttribute,
+            Entry::EnvironmentVariableDescription(_) => EntryType::EnvironmentVariableDescription,
+            Entry::EnvironmentVariableAttribute(_) => EntryType::EnvironmentVariableAttribute,
             Entry::MessageDefinition(_) => EntryType::MessageDefinition,
             Entry::MessageDescription(_) => EntryType::MessageDescription,
             Entry::MessageAttribute(_) => EntryType::MessageAttribute,
             Entry::SignalDefinition(_) => EntryType::SignalDefinition,
             Entry::SignalDescription(_) => EntryType::SignalDescription,
             Entry::SignalAttribute(_) => EntryType::SignalAttribute,
+            Entry::ValueDescription(_) => EntryType::ValueDescription,
+            Entry::SignalValueType(_) => EntryType::SignalValueType,
+            Entry::ExtendedMultiplexing(_) => EntryType::ExtendedMultiplexing,
+            Entry::AttributeDefinition(_) => EntryType::AttributeDefinition,
+            Entry::AttributeDefault(_) => EntryType::AttributeDefault,
+            Entry::RelationAttributeDefault(_) => EntryType::RelationAttributeDefault,
             Entry::Unknown(_) => EntryType::Unknown,
         }
     }
@@ -146,9 +365,17 @@ pub enum EntryType {
 
     BusConfiguration,
 
-    // CanNodes,
-    // CanNodesDescription,
-    // CanNodesAttribute
+    NetworkDescription,
+    NetworkAttribute,
+
+    ValueTable,
+
+    CanNodes,
+    CanNodesDescription,
+    CanNodesAttribute,
+
+    EnvironmentVariableDescription,
+    EnvironmentVariableAttribute,
 
     MessageDefinition,
     MessageDescription,
@@ -160,8 +387,13 @@ pub enum EntryType {
     SignalAttribute,
 //    SignalAttributeDefinition,
 
-    // AttributeDefinition,
-    // AttributeDefault,
+    ValueDescription,
+    SignalValueType,
+    ExtendedMultiplexing,
+
+    AttributeDefinition,
+    AttributeDefault,
+    RelationAttributeDefault,
     // Attribute
 
     Unknown,
@@ -173,12 +405,26 @@ impl Display for EntryType {
         let entry_str = match *self {
             EntryType::Version => "Version",
             EntryType::BusConfiguration => "BusConfiguration",
+            EntryType::NetworkDescription => "NetworkDescription",
+            EntryType::NetworkAttribute => "NetworkAttribute",
+            EntryType::ValueTable => "ValueTable",
+            EntryType::CanNodes => "CanNodes",
+            EntryType::CanNodesDescription => "CanNodesDescription",
+            EntryType::CanNodesAttribute => "CanNodesAttribute",
+            EntryType::EnvironmentVariableDescription => "EnvironmentVariableDescription",
+            EntryType::EnvironmentVariableAttribute => "EnvironmentVariableAttribute",
             EntryType::MessageDefinition => "MessageDefinition",
             EntryType::MessageDescription => "MessageDescription",
             EntryType::MessageAttribute => "MessageAttribute",
             EntryType::SignalDefinition => "SignalDefinition",
             EntryType::SignalDescription => "SignalDescription",
             EntryType::SignalAttribute => "SignalAttribute",
+            EntryType::ValueDescription => "ValueDescription",
+            EntryType::SignalValueType => "SignalValueType",
+            EntryType::ExtendedMultiplexing => "ExtendedMultiplexing",
+            EntryType::AttributeDefinition => "AttributeDefinition",
+            EntryType::AttributeDefault => "AttributeDefault",
+            EntryType::RelationAttributeDefault => "RelationAttributeDefault",
 
             EntryType::Unknown => "Unknown",
         };
@@ -193,6 +439,11 @@ pub struct ParseEntryError {
 }
 
 impl ParseEntryError {
+    /// Returns the kind of parsing failure.
+    pub fn kind(&self) -> &EntryErrorKind {
+        &self.kind
+    }
+
     #[doc(hidden)]
     pub fn __description(&self) -> &str {
         self.kind.__description()
@@ -220,13 +471,12 @@ impl Error for ParseEntryError {
     }
 }
 
-/// Internal type DBC `Entry` parsing error.
+/// Type of `ParseEntryError`.
 #[derive(Debug, Clone, Eq, PartialEq)]
-enum EntryErrorKind {
+pub enum EntryErrorKind {
     /// Could not find a regex match for input
     RegexNoMatch,
     /// Integer could not be converted into valid `EntryType`
-    #[allow(dead_code)]
     UnknownEntryType(i32),
 }
 
@@ -272,24 +522,343 @@ impl FromStr for Entry {
     }
 }
 
-/// Probably some spec to determine a type when generating structs
-/// Here an enum will be dispatched instead (e.g., VAL_)
+/// Error returned when a library can't take in a DBC `Entry`, or warning collected while loading
+/// a DBC file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadError {
+    kind: LoadErrorKind,
+    entry: Option<Box<Entry>>,
+    line: Option<usize>,
+    source_line: Option<String>,
+}
+
+impl LoadError {
+    /// Creates a new `LoadError` for the offending `entry`, if any.
+    pub fn new(kind: LoadErrorKind, entry: Option<Entry>) -> Self {
+        LoadError {
+            kind,
+            entry: entry.map(Box::new),
+            line: None,
+            source_line: None,
+        }
+    }
+
+    /// Returns the kind of loading failure.
+    pub fn kind(&self) -> &LoadErrorKind {
+        &self.kind
+    }
+
+    /// Returns the offending entry, if the input could be parsed.
+    pub fn entry(&self) -> Option<&Entry> {
+        self.entry.as_deref()
+    }
+
+    /// Returns the 1-based line number the offending input starts on, when loading a whole file.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Returns the text of the line the offending input starts on, when loading a whole file.
+    pub fn source_line(&self) -> Option<&str> {
+        self.source_line.as_deref()
+    }
+
+    #[doc(hidden)]
+    pub fn __description(&self) -> &str {
+        self.kind.__description()
+    }
+
+    #[doc(hidden)]
+    pub fn __cause(&self) -> Option<&dyn Error> {
+        self.kind.__cause()
+    }
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.__description()),
+            None => write!(f, "{}", self.__description()),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn description(&self) -> &str {
+        self.__description()
+    }
+
+    fn cause(&self) -> Option<&dyn Error> {
+        self.__cause()
+    }
+}
+
+/// Type of `LoadError`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadErrorKind {
+    /// Input which couldn't be parsed as a DBC entry
+    Unparseable,
+    /// Entry not used by the library, e.g., `VERSION`
+    UnsupportedEntry(EntryType),
+    /// Entry refers to a message or signal which hasn't been defined, e.g., `SG_` before `BO_`
+    MissingDefinition,
+    /// Entry conflicting with the library's definitions, or with invalid values
+    InvalidEntry(String),
+}
+
+impl LoadErrorKind {
+    #[doc(hidden)]
+    pub fn __description(&self) -> &str {
+        match self {
+            LoadErrorKind::Unparseable => "input could not be parsed as a DBC entry",
+            LoadErrorKind::UnsupportedEntry(_) => "entry not used by the library",
+            LoadErrorKind::MissingDefinition => "entry refers to an undefined message or signal",
+            LoadErrorKind::InvalidEntry(reason) => reason,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn __cause(&self) -> Option<&dyn Error> {
+        None
+    }
+}
+
+impl Display for LoadErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let s = self.__description();
+        write!(f, "{s}")
+    }
+}
+
+impl From<LoadErrorKind> for LoadError {
+    fn from(kind: LoadErrorKind) -> Self {
+        LoadError::new(kind, None)
+    }
+}
+
+/// Internal function for loading DBC file contents entry by entry through `add_entry`.  Input
+/// which can't be parsed is skipped up to the next line.  Returns the errors from `add_entry`
+/// and for skipped input, annotated with their source line.  Entries which the library doesn't
+/// use are expected in any DBC file, and aren't reported.
+pub(crate) fn load_entries<F>(data: &str, mut add_entry: F) -> Vec<LoadError>
+where
+    F: FnMut(Entry) -> Result<(), LoadError>,
+{
+    let mut warnings = Vec::new();
+    let mut line = 1;
+    let mut i = data;
+
+    while !i.is_empty() {
+        let source_line = i.lines().next().unwrap_or_default();
+        let (rest, error) = match parser::entry(i) {
+            Ok((rest, entry)) => (rest, add_entry(entry).err()),
+            Err(nom::Err::Incomplete(_)) => {
+                // Trailing input without a line ending
+                let error =
+                    Some(LoadErrorKind::Unparseable.into()).filter(|_| !i.trim().is_empty());
+                ("", error)
+            }
+            Err(_) => {
+                let skip = i.find('\n').map_or(i.len(), |end| end + 1);
+                (&i[skip..], Some(LoadErrorKind::Unparseable.into()))
+            }
+        };
+
+        match error {
+            Some(LoadError {
+                kind: LoadErrorKind::UnsupportedEntry(_),
+                ..
+            })
+            | None => {}
+            Some(mut error) => {
+                error.line = Some(line);
+                error.source_line = Some(source_line.to_string());
+                warnings.push(error);
+            }
+        }
+
+        line += i[..i.len() - rest.len()].matches('\n').count();
+        i = rest;
+    }
+
+    warnings
+}
+
+/// Mapping of raw signal values to their enumerated labels (e.g., `VAL_`).
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct ValueDefinition {
-    values: Vec<String>,
+    values: Vec<(i64, String)>,
 }
 
+impl ValueDefinition {
+    /// Creates a new `ValueDefinition` from `(raw value, label)` pairs.
+    pub fn new(values: Vec<(i64, String)>) -> Self {
+        ValueDefinition { values }
+    }
+
+    /// Returns the label for a raw signal value, if one is defined.
+    pub fn get(&self, raw: i64) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(value, _)| *value == raw)
+            .map(|(_, label)| label.as_str())
+    }
+
+    /// Iterates over the `(raw value, label)` pairs in definition order.
+    pub fn iter(&self) -> impl Iterator<Item = (i64, &str)> {
+        self.values
+            .iter()
+            .map(|(value, label)| (*value, label.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// Type of object an attribute (`BA_DEF_`) applies to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AttributeObjectType {
+    /// No object prefix, applies to the whole database
+    Network,
+    /// `BU_`
+    Node,
+    /// `BO_`
+    Message,
+    /// `SG_`
+    Signal,
+    /// `EV_`
+    EnvironmentVariable,
+}
+
+/// Value type of an attribute definition (`BA_DEF_`).  Integer and float ranges of `0 0` are
+/// treated as unbounded.
+#[derive(Debug, Clone, PartialEq)]
 pub enum AttributeType {
     /// Integer type with min/max values
-    Int { min: i32, max: i32 },
+    Int { min: i64, max: i64 },
+    /// Hexadecimal integer type with min/max values
+    Hex { min: i64, max: i64 },
     /// Float type with min/max values
-    Float { min: f32, max: f32 },
+    Float { min: f64, max: f64 },
     /// String type
     String,
     /// Enum type, represented as a vector of `String`s
     Enum(Vec<String>),
 }
 
+impl AttributeType {
+    /// Converts a parsed attribute value (`BA_`, `BA_DEF_DEF_`) into the value of this type,
+    /// e.g., resolving an enum index or label into `AttributeValue::Enum`.  Returns `None` if
+    /// the value doesn't conform to the type or its range.
+    pub fn resolve(&self, value: &AttributeValue) -> Option<AttributeValue> {
+        let in_range = |min, max, value| (min == 0 && max == 0) || (min <= value && value <= max);
+
+        match (self, value) {
+            (AttributeType::Int { min, max }, AttributeValue::Int(value))
+                if in_range(*min, *max, *value) =>
+            {
+                Some(AttributeValue::Int(*value))
+            }
+            (AttributeType::Hex { min, max }, AttributeValue::Int(value))
+            | (AttributeType::Hex { min, max }, AttributeValue::Hex(value))
+                if in_range(*min, *max, *value) =>
+            {
+                Some(AttributeValue::Hex(*value))
+            }
+            (AttributeType::Float { min, max }, AttributeValue::Int(_))
+            | (AttributeType::Float { min, max }, AttributeValue::Float(_)) => {
+                let value = value.as_f64()?;
+                if (*min == 0.0 && *max == 0.0) || (*min <= value && value <= *max) {
+                    Some(AttributeValue::Float(value))
+                } else {
+                    None
+                }
+            }
+            (AttributeType::String, AttributeValue::String(value)) => {
+                Some(AttributeValue::String(value.clone()))
+            }
+            (AttributeType::Enum(labels), AttributeValue::Int(index)) => {
+                let index = usize::try_from(*index).ok()?;
+                labels
+                    .get(index)
+                    .map(|label| AttributeValue::Enum(index, label.clone()))
+            }
+            (AttributeType::Enum(labels), AttributeValue::String(label))
+            | (AttributeType::Enum(labels), AttributeValue::Enum(_, label)) => labels
+                .iter()
+                .position(|candidate| candidate == label)
+                .map(|index| AttributeValue::Enum(index, label.clone())),
+            _ => None,
+        }
+    }
+
+    /// Returns whether an attribute value conforms to this type and its range.
+    pub fn is_valid(&self, value: &AttributeValue) -> bool {
+        self.resolve(value).is_some()
+    }
+}
+
+/// Value of an attribute (`BA_`) or attribute default (`BA_DEF_DEF_`).
+///
+/// The parser only distinguishes integers, floats and quoted strings.  `Hex` and `Enum` values
+/// are produced by resolving a value against its `AttributeType`, as `DbcLibrary` does when
+/// loading a file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    Int(i64),
+    Hex(i64),
+    Float(f64),
+    String(String),
+    /// Enum value, represented by its index and label
+    Enum(usize, String),
+}
+
+impl AttributeValue {
+    /// Returns the integer value of `Int`, `Hex` and `Enum` (index) values.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            AttributeValue::Int(value) | AttributeValue::Hex(value) => Some(value),
+            AttributeValue::Enum(index, _) => Some(index as i64),
+            _ => None,
+        }
+    }
+
+    /// Returns the numeric value of `Int`, `Hex` and `Float` values.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            AttributeValue::Int(value) | AttributeValue::Hex(value) => Some(value as f64),
+            AttributeValue::Float(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the text of `String` values, or the label of `Enum` values.
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            AttributeValue::String(ref value) | AttributeValue::Enum(_, ref value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl Display for AttributeValue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            AttributeValue::Int(value) => write!(f, "{value}"),
+            AttributeValue::Hex(value) => write!(f, "{value:#X}"),
+            AttributeValue::Float(value) => write!(f, "{value}"),
+            AttributeValue::String(value) | AttributeValue::Enum(_, value) => {
+                write!(f, "{value}")
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     macro_rules! test_entry {
@@ -348,6 +917,279 @@ mod tests {
         Version("A version string".to_string())
     );
 
+    test_entry!(
+        network_description,
+        NetworkDescription,
+        "CM_ \"J1939 sample database\";\n",
+        NetworkDescription("J1939 sample database".to_string())
+    );
+
+    test_entry!(
+        network_attribute,
+        NetworkAttribute,
+        "BA_ \"ProtocolType\" \"J1939\";\n",
+        NetworkAttribute {
+            name: "ProtocolType".to_string(),
+            value: AttributeValue::String("J1939".to_string())
+        }
+    );
+
+    test_entry!(
+        value_table,
+        ValueTable,
+        "VAL_TABLE_ StarterMode 15 \"NotAvailable\" 14 \"Error\" 0 \"StartNotRqed\" ;\n",
+        ValueTable {
+            name: "StarterMode".to_string(),
+            values: ValueDefinition::new(vec![
+                (15, "NotAvailable".to_string()),
+                (14, "Error".to_string()),
+                (0, "StartNotRqed".to_string()),
+            ])
+        }
+    );
+
+    test_entry!(
+        can_nodes,
+        CanNodes,
+        "BU_: Engine Transmission\n",
+        CanNodes(vec!["Engine".to_string(), "Transmission".to_string()])
+    );
+
+    test_entry!(
+        can_nodes_description,
+        CanNodesDescription,
+        "CM_ BU_ Engine \"Engine control module\";\n",
+        CanNodesDescription {
+            node_name: "Engine".to_string(),
+            description: "Engine control module".to_string()
+        }
+    );
+
+    test_entry!(
+        can_nodes_attribute,
+        CanNodesAttribute,
+        "BA_ \"NmStationAddress\" BU_ Engine 0;\n",
+        CanNodesAttribute {
+            name: "NmStationAddress".to_string(),
+            node_name: "Engine".to_string(),
+            value: AttributeValue::Int(0)
+        }
+    );
+
+    test_entry!(
+        attribute_definition,
+        AttributeDefinition,
+        "BA_DEF_ BO_ \"GenMsgCycleTime\" INT 0 65535;\n",
+        AttributeDefinition {
+            object_type: AttributeObjectType::Message,
+            name: "GenMsgCycleTime".to_string(),
+            value_type: AttributeType::Int { min: 0, max: 65535 }
+        }
+    );
+
+    mod attribute_types {
+        mod hex {
+            test_entry!(
+                attribute_definition,
+                AttributeDefinition,
+                "BA_DEF_ BU_ \"NmStationAddress\" HEX 0 254;\n",
+                AttributeDefinition {
+                    object_type: AttributeObjectType::Node,
+                    name: "NmStationAddress".to_string(),
+                    value_type: AttributeType::Hex { min: 0, max: 254 }
+                }
+            );
+        }
+
+        mod float {
+            test_entry!(
+                attribute_definition,
+                AttributeDefinition,
+                "BA_DEF_ SG_ \"GenSigStartValue\" FLOAT -1.5 1E+006;\n",
+                AttributeDefinition {
+                    object_type: AttributeObjectType::Signal,
+                    name: "GenSigStartValue".to_string(),
+                    value_type: AttributeType::Float {
+                        min: -1.5,
+                        max: 1000000.0
+                    }
+                }
+            );
+        }
+
+        mod string {
+            test_entry!(
+                attribute_definition,
+                AttributeDefinition,
+                "BA_DEF_  \"BusType\" STRING ;\n",
+                AttributeDefinition {
+                    object_type: AttributeObjectType::Network,
+                    name: "BusType".to_string(),
+                    value_type: AttributeType::String
+                }
+            );
+        }
+
+        mod enumeration {
+            test_entry!(
+                attribute_definition,
+                AttributeDefinition,
+                "BA_DEF_ EV_ \"EnvType\" ENUM  \"Off\",\"On\", \"Auto\";\n",
+                AttributeDefinition {
+                    object_type: AttributeObjectType::EnvironmentVariable,
+                    name: "EnvType".to_string(),
+                    value_type: AttributeType::Enum(vec![
+                        "Off".to_string(),
+                        "On".to_string(),
+                        "Auto".to_string()
+                    ])
+                }
+            );
+        }
+    }
+
+    test_entry!(
+        attribute_default,
+        AttributeDefault,
+        "BA_DEF_DEF_ \"GenMsgCycleTime\" 100;\n",
+        AttributeDefault {
+            name: "GenMsgCycleTime".to_string(),
+            value: AttributeValue::Int(100)
+        }
+    );
+
+    mod attribute_defaults {
+        mod string {
+            test_entry!(
+                attribute_default,
+                AttributeDefault,
+                "BA_DEF_DEF_  \"BusType\" \"CAN\";\n",
+                AttributeDefault {
+                    name: "BusType".to_string(),
+                    value: AttributeValue::String("CAN".to_string())
+                }
+            );
+        }
+
+        mod relation {
+            test_entry!(
+                relation_attribute_default,
+                RelationAttributeDefault,
+                "BA_DEF_DEF_REL_ \"GenSigTimeoutTime\" -1.5;\n",
+                AttributeDefault {
+                    name: "GenSigTimeoutTime".to_string(),
+                    value: AttributeValue::Float(-1.5)
+                }
+            );
+        }
+    }
+
+    mod attribute_values {
+        mod negative {
+            test_entry!(
+                message_attribute,
+                MessageAttribute,
+                "BA_ \"GenMsgStartValue\" BO_ 2364539904 -40;\n",
+                MessageAttribute {
+                    name: "GenMsgStartValue".to_string(),
+                    signal_name: "".to_string(),
+                    id: 2364539904,
+                    value: AttributeValue::Int(-40)
+                }
+            );
+        }
+
+        mod float {
+            test_entry!(
+                signal_attribute,
+                SignalAttribute,
+                "BA_ \"GenSigStartValue\" SG_ 2364539904 Engine_Speed 0.5;\n",
+                SignalAttribute {
+                    name: "GenSigStartValue".to_string(),
+                    id: 2364539904,
+                    signal_name: "Engine_Speed".to_string(),
+                    value: AttributeValue::Float(0.5)
+                }
+            );
+        }
+
+        mod string {
+            test_entry!(
+                can_nodes_attribute,
+                CanNodesAttribute,
+                "BA_ \"ILUsed\" BU_ Engine \"Yes\";\n",
+                CanNodesAttribute {
+                    name: "ILUsed".to_string(),
+                    node_name: "Engine".to_string(),
+                    value: AttributeValue::String("Yes".to_string())
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn attribute_type_resolve() {
+        use crate::dbc::{AttributeType, AttributeValue};
+
+        let int = AttributeType::Int { min: -10, max: 10 };
+        assert_eq!(
+            int.resolve(&AttributeValue::Int(-10)),
+            Some(AttributeValue::Int(-10))
+        );
+        assert!(!int.is_valid(&AttributeValue::Int(11)));
+        assert!(!int.is_valid(&AttributeValue::Float(1.5)));
+        assert!(AttributeType::Int { min: 0, max: 0 }.is_valid(&AttributeValue::Int(65535)));
+
+        let hex = AttributeType::Hex { min: 0, max: 255 };
+        assert_eq!(
+            hex.resolve(&AttributeValue::Int(254)),
+            Some(AttributeValue::Hex(254))
+        );
+
+        let float = AttributeType::Float { min: 0.0, max: 1.0 };
+        assert_eq!(
+            float.resolve(&AttributeValue::Int(1)),
+            Some(AttributeValue::Float(1.0))
+        );
+        assert!(!float.is_valid(&AttributeValue::Float(1.5)));
+
+        let enumeration = AttributeType::Enum(vec!["No".to_string(), "Yes".to_string()]);
+        assert_eq!(
+            enumeration.resolve(&AttributeValue::Int(1)),
+            Some(AttributeValue::Enum(1, "Yes".to_string()))
+        );
+        assert_eq!(
+            enumeration.resolve(&AttributeValue::String("No".to_string())),
+            Some(AttributeValue::Enum(0, "No".to_string()))
+        );
+        assert!(!enumeration.is_valid(&AttributeValue::Int(2)));
+        assert!(!enumeration.is_valid(&AttributeValue::String("Maybe".to_string())));
+
+        assert!(AttributeType::String.is_valid(&AttributeValue::String("anything".to_string())));
+        assert!(!AttributeType::String.is_valid(&AttributeValue::Int(0)));
+    }
+
+    test_entry!(
+        environment_variable_description,
+        EnvironmentVariableDescription,
+        "CM_ EV_ IgnitionSwitch \"Ignition key position\";\n",
+        EnvironmentVariableDescription {
+            env_var_name: "IgnitionSwitch".to_string(),
+            description: "Ignition key position".to_string()
+        }
+    );
+
+    test_entry!(
+        environment_variable_attribute,
+        EnvironmentVariableAttribute,
+        "BA_ \"EnvStartValue\" EV_ IgnitionSwitch 2;\n",
+        EnvironmentVariableAttribute {
+            name: "EnvStartValue".to_string(),
+            env_var_name: "IgnitionSwitch".to_string(),
+            value: AttributeValue::Int(2)
+        }
+    );
+
     test_entry!(
         message_definition,
         MessageDefinition,
@@ -379,7 +1221,7 @@ mod tests {
             name: "SingleFrame".to_string(),
             signal_name: "".to_string(),
             id: 2364539904,
-            value: "0".to_string()
+            value: AttributeValue::Int(0)
         }
     );
 
@@ -389,10 +1231,12 @@ mod tests {
         " SG_ Engine_Speed : 24|16@1+ (0.125,0) [0|8031.88] \"rpm\" Vector__XXX\n",
         SignalDefinition {
             name: "Engine_Speed".to_string(),
+            multiplex_indicator: MultiplexIndicator::Plain,
             start_bit: 24,
             bit_len: 16,
             little_endian: true,
             signed: false,
+            value_type: ValueType::Integer,
             scale: 0.125,
             offset: 0.0,
             min_value: 0.0,
@@ -402,6 +1246,75 @@ mod tests {
         }
     );
 
+    mod multiplexed_signals {
+        test_entry!(
+            signal_definition,
+            SignalDefinition,
+            " SG_ PropDiag_Page M : 0|8@1+ (1,0) [0|255] \"\" Vector__XXX\n",
+            SignalDefinition {
+                name: "PropDiag_Page".to_string(),
+                multiplex_indicator: MultiplexIndicator::Multiplexor,
+                start_bit: 0,
+                bit_len: 8,
+                little_endian: true,
+                signed: false,
+                value_type: ValueType::Integer,
+                scale: 1.0,
+                offset: 0.0,
+                min_value: 0.0,
+                max_value: 255.0,
+                units: "".to_string(),
+                receiving_node: "Vector__XXX".to_string()
+            }
+        );
+
+        mod multiplexed_multiplexor {
+            test_entry!(
+                signal_definition,
+                SignalDefinition,
+                " SG_ PropDiag_Status m3M : 8|8@1+ (1,0) [0|255] \"\" Vector__XXX\n",
+                SignalDefinition {
+                    name: "PropDiag_Status".to_string(),
+                    multiplex_indicator: MultiplexIndicator::MultiplexedMultiplexor(3),
+                    start_bit: 8,
+                    bit_len: 8,
+                    little_endian: true,
+                    signed: false,
+                    value_type: ValueType::Integer,
+                    scale: 1.0,
+                    offset: 0.0,
+                    min_value: 0.0,
+                    max_value: 255.0,
+                    units: "".to_string(),
+                    receiving_node: "Vector__XXX".to_string()
+                }
+            );
+        }
+
+        mod multiplexed {
+            test_entry!(
+                signal_definition,
+                SignalDefinition,
+                " SG_ PropDiag_FuelRate m2 : 8|16@1+ (0.05,0) [0|3212.75] \"L/h\" Vector__XXX\n",
+                SignalDefinition {
+                    name: "PropDiag_FuelRate".to_string(),
+                    multiplex_indicator: MultiplexIndicator::Multiplexed(2),
+                    start_bit: 8,
+                    bit_len: 16,
+                    little_endian: true,
+                    signed: false,
+                    value_type: ValueType::Integer,
+                    scale: 0.05,
+                    offset: 0.0,
+                    min_value: 0.0,
+                    max_value: 3212.75,
+                    units: "L/h".to_string(),
+                    receiving_node: "Vector__XXX".to_string()
+                }
+            );
+        }
+    }
+
     test_entry!(
         signal_description,
         SignalDescription,
@@ -421,7 +1334,47 @@ mod tests {
             name: "SPN".to_string(),
             id: 2364539904,
             signal_name: "Engine_Speed".to_string(),
-            value: "190".to_string()
+            value: AttributeValue::Int(190)
+        }
+    );
+
+    test_entry!(
+        value_description,
+        ValueDescription,
+        "VAL_ 2364539904 Engine_Starter_Mode 15 \"NotAvailable\" 14 \"Error\" 0 \"StartNotRqed\" ;\n",
+        ValueDescription {
+            id: 2364539904,
+            signal_name: "Engine_Starter_Mode".to_string(),
+            values: ValueDefinition::new(vec![
+                (15, "NotAvailable".to_string()),
+                (14, "Error".to_string()),
+                (0, "StartNotRqed".to_string()),
+            ])
+        }
+    );
+
+    test_entry!(
+        signal_value_type,
+        SignalValueType,
+        "SIG_VALTYPE_ 2566848768 PropFuel_Density : 1;\n",
+        SignalValueType {
+            id: 2566848768,
+            signal_name: "PropFuel_Density".to_string(),
+            value_type: ValueType::Float32
+        }
+    );
+
+    test_entry!(
+        extended_multiplexing,
+        ExtendedMultiplexing,
+        "SG_MUL_VAL_ 2566848512 PropDiag_Status PropDiag_Page 3-3, 5-7;\n",
+        ExtendedMultiplexing {
+            id: 2566848512,
+            signal_name: "PropDiag_Status".to_string(),
+            selector: MultiplexSelector {
+                multiplexor_name: "PropDiag_Page".to_string(),
+                ranges: vec![(3, 3), (5, 7)],
+            }
         }
     );
 
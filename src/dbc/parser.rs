@@ -1,7 +1,7 @@
 //! Nom-based parsers for Entry types
 
 use super::*;
-use nom::{digit, float, line_ending, space, space0, AsChar};
+use nom::{digit, double, float, line_ending, recognize_float, space, space0, AsChar};
 use std::str::FromStr;
 
 // TODO: convert `tag!(" ")` to `space`
@@ -25,12 +25,26 @@ named! {
 named!(pub entry<&str, Entry>, alt!(
     version                => { Entry::Version } |
     bus_configuration      => { Entry::BusConfiguration } |
+    network_description    => { Entry::NetworkDescription } |
+    network_attribute      => { Entry::NetworkAttribute } |
+    value_table            => { Entry::ValueTable } |
+    can_nodes              => { Entry::CanNodes } |
+    can_nodes_description  => { Entry::CanNodesDescription } |
+    can_nodes_attribute    => { Entry::CanNodesAttribute } |
+    environment_variable_description => { Entry::EnvironmentVariableDescription } |
+    environment_variable_attribute   => { Entry::EnvironmentVariableAttribute } |
     message_definition     => { Entry::MessageDefinition } |
     message_description    => { Entry::MessageDescription } |
     message_attribute      => { Entry::MessageAttribute } |
     signal_definition      => { Entry::SignalDefinition } |
     signal_description     => { Entry::SignalDescription } |
     signal_attribute       => { Entry::SignalAttribute } |
+    value_description      => { Entry::ValueDescription } |
+    signal_value_type      => { Entry::SignalValueType } |
+    extended_multiplexing  => { Entry::ExtendedMultiplexing } |
+    attribute_definition   => { Entry::AttributeDefinition } |
+    attribute_default      => { Entry::AttributeDefault } |
+    relation_attribute_default => { Entry::RelationAttributeDefault } |
     unknown                => { Entry::Unknown }
 ));
 
@@ -65,10 +79,183 @@ named!(pub bus_configuration<&str, BusConfiguration>,
     )
 );
 
+named!(
+    signed_integer<&str, i64>,
+    map_res!(
+        recognize!(pair!(opt!(tag!("-")), digit)),
+        FromStr::from_str)
+);
+
+named!(
+    value_labels<&str, ValueDefinition>,
+    map!(
+        many0!(
+            do_parse!(
+                value: signed_integer >>
+                space >>
+                label: quoted_str >>
+                space0 >>
+                ( (value, label) )
+            )
+        ),
+        ValueDefinition::new)
+);
+
+named!(
+    attribute_value<&str, AttributeValue>,
+    alt!(
+        quoted_str => { AttributeValue::String } |
+        map_opt!(
+            recognize_float,
+            |value: &str| value
+                .parse()
+                .map(AttributeValue::Int)
+                .or_else(|_| value.parse().map(AttributeValue::Float))
+                .ok())
+    )
+);
+
 fn is_alphanumeric_extended(c: char) -> bool {
     c.is_alphanum() || c == '_'
 }
 
+named!(pub value_table<&str, ValueTable>,
+    do_parse!(
+        tag!("VAL_TABLE_")   >>
+        space >>
+        name: take_while!(is_alphanumeric_extended) >>
+        space >>
+        values: value_labels >>
+        tag!(";") >>
+        line_ending >>
+        ( ValueTable {
+            name: name.to_string(),
+            values: values
+        } )
+    )
+);
+
+named!(pub network_description<&str, NetworkDescription>,
+    do_parse!(
+        tag!("CM_")   >>
+        space >>
+        description: quoted_str >>
+        tag!(";") >>
+        line_ending >>
+        ( NetworkDescription(description) )
+    )
+);
+
+named!(pub network_attribute<&str, NetworkAttribute>,
+    do_parse!(
+        tag!("BA_")   >>
+        space >>
+        name: quoted_str >>
+        space >>
+        value: attribute_value >>
+        space0 >>
+        tag!(";") >>
+        line_ending >>
+        ( NetworkAttribute {
+            name: name,
+            value: value
+        } )
+    )
+);
+
+named!(pub can_nodes<&str, CanNodes>,
+    do_parse!(
+        tag!("BU_:")   >>
+        nodes: many0!(
+            preceded!(
+                space,
+                take_while1!(is_alphanumeric_extended))) >>
+        space0 >>
+        line_ending >>
+        ( CanNodes(nodes.into_iter().map(String::from).collect()) )
+    )
+);
+
+named!(pub can_nodes_description<&str, CanNodesDescription>,
+    do_parse!(
+        tag!("CM_")   >>
+        space >>
+        tag!("BU_")   >>
+        space >>
+        node_name: take_while1!(is_alphanumeric_extended) >>
+        space >>
+        description: quoted_str >>
+        tag!(";") >>
+        line_ending >>
+        ( CanNodesDescription {
+            node_name: node_name.to_string(),
+            description: description
+        } )
+    )
+);
+
+named!(pub can_nodes_attribute<&str, CanNodesAttribute>,
+    do_parse!(
+        tag!("BA_")   >>
+        space >>
+        name: quoted_str >>
+        space >>
+        tag!("BU_")   >>
+        space >>
+        node_name: take_while1!(is_alphanumeric_extended) >>
+        space >>
+        value: attribute_value >>
+        space0 >>
+        tag!(";") >>
+        line_ending >>
+        ( CanNodesAttribute {
+            name: name,
+            node_name: node_name.to_string(),
+            value: value
+        } )
+    )
+);
+
+named!(pub environment_variable_description<&str, EnvironmentVariableDescription>,
+    do_parse!(
+        tag!("CM_")   >>
+        space >>
+        tag!("EV_")   >>
+        space >>
+        env_var_name: take_while1!(is_alphanumeric_extended) >>
+        space >>
+        description: quoted_str >>
+        tag!(";") >>
+        line_ending >>
+        ( EnvironmentVariableDescription {
+            env_var_name: env_var_name.to_string(),
+            description: description
+        } )
+    )
+);
+
+named!(pub environment_variable_attribute<&str, EnvironmentVariableAttribute>,
+    do_parse!(
+        tag!("BA_")   >>
+        space >>
+        name: quoted_str >>
+        space >>
+        tag!("EV_")   >>
+        space >>
+        env_var_name: take_while1!(is_alphanumeric_extended) >>
+        space >>
+        value: attribute_value >>
+        space0 >>
+        tag!(";") >>
+        line_ending >>
+        ( EnvironmentVariableAttribute {
+            name: name,
+            env_var_name: env_var_name.to_string(),
+            value: value
+        } )
+    )
+);
+
 // FIXME: `space` isn't really correct since there should only be ONE (probably need alt)
 named!(pub message_definition<&str, MessageDefinition>,
     do_parse!(
@@ -131,18 +318,36 @@ named!(pub message_attribute<&str, MessageAttribute>,
             digit,
             FromStr::from_str) >>
         space >>
-        value: digit >>
+        value: attribute_value >>
+        space0 >>
         tag!(";") >>
         line_ending >>
         ( MessageAttribute {
             name: name,
             signal_name: "".to_string(),
             id: id,
-            value: value.to_string()
+            value: value
         } )
     )
 );
 
+named!(multiplex_indicator<&str, MultiplexIndicator>,
+    alt!(
+        tag!("M") => { |_| MultiplexIndicator::Multiplexor } |
+        do_parse!(
+            tag!("m") >>
+            value: map_res!(
+                digit,
+                FromStr::from_str) >>
+            multiplexor: opt!(tag!("M")) >>
+            ( match multiplexor {
+                Some(_) => MultiplexIndicator::MultiplexedMultiplexor(value),
+                None => MultiplexIndicator::Multiplexed(value),
+            } )
+        )
+    )
+);
+
 named!(pub signal_definition<&str, SignalDefinition>,
     do_parse!(
         space >>
@@ -150,6 +355,7 @@ named!(pub signal_definition<&str, SignalDefinition>,
         space >>
         name: take_until_either!(" \t") >>
         space >>
+        multiplex_indicator: opt!(terminated!(multiplex_indicator, space)) >>
         tag!(":") >>
         space >>
         start_bit: map_res!(
@@ -183,10 +389,12 @@ named!(pub signal_definition<&str, SignalDefinition>,
         line_ending >>
         ( SignalDefinition {
             name: name.to_string(),
+            multiplex_indicator: multiplex_indicator.unwrap_or_default(),
             start_bit: start_bit,
             bit_len: bit_len,
             little_endian: little_endian,
             signed: signed,
+            value_type: ValueType::Integer,
             scale: scale,
             offset: offset,
             min_value: min_value,
@@ -234,14 +442,210 @@ named!(pub signal_attribute<&str, SignalAttribute>,
         space >>
         signal_name: take_until_either!(" \t") >>
         space >>
-        value: digit >>
+        value: attribute_value >>
+        space0 >>
         tag!(";") >>
         line_ending >>
         ( SignalAttribute {
             name: name,
             id: id,
             signal_name: signal_name.to_string(),
-            value: value.to_string()
+            value: value
+        } )
+    )
+);
+
+named!(pub value_description<&str, ValueDescription>,
+    do_parse!(
+        tag!("VAL_")   >>
+        space >>
+        id: map_res!(
+            digit,
+            FromStr::from_str) >>
+        space >>
+        signal_name: take_until_either!(" \t") >>
+        space >>
+        values: value_labels >>
+        tag!(";") >>
+        line_ending >>
+        ( ValueDescription {
+            id: id,
+            signal_name: signal_name.to_string(),
+            values: values
+        } )
+    )
+);
+
+named!(pub signal_value_type<&str, SignalValueType>,
+    do_parse!(
+        tag!("SIG_VALTYPE_") >>
+        space >>
+        id: map_res!(
+            digit,
+            FromStr::from_str) >>
+        space >>
+        signal_name: take_until_either!(" \t:") >>
+        space0 >>
+        tag!(":") >>
+        space0 >>
+        value_type: map_opt!(
+            map_res!(digit, u8::from_str),
+            ValueType::from_code) >>
+        space0 >>
+        tag!(";") >>
+        line_ending >>
+        ( SignalValueType {
+            id: id,
+            signal_name: signal_name.to_string(),
+            value_type: value_type
+        } )
+    )
+);
+
+named!(
+    multiplexor_range<&str, (u64, u64)>,
+    do_parse!(
+        low: map_res!(
+            digit,
+            FromStr::from_str) >>
+        tag!("-") >>
+        high: map_res!(
+            digit,
+            FromStr::from_str) >>
+        ( (low, high) )
+    )
+);
+
+named!(pub extended_multiplexing<&str, ExtendedMultiplexing>,
+    do_parse!(
+        tag!("SG_MUL_VAL_") >>
+        space >>
+        id: map_res!(
+            digit,
+            FromStr::from_str) >>
+        space >>
+        signal_name: take_until_either!(" \t") >>
+        space >>
+        multiplexor_name: take_until_either!(" \t") >>
+        space >>
+        ranges: separated_nonempty_list!(
+            terminated!(tag!(","), space0),
+            multiplexor_range) >>
+        space0 >>
+        tag!(";") >>
+        line_ending >>
+        ( ExtendedMultiplexing {
+            id: id,
+            signal_name: signal_name.to_string(),
+            selector: MultiplexSelector {
+                multiplexor_name: multiplexor_name.to_string(),
+                ranges: ranges,
+            }
+        } )
+    )
+);
+
+named!(
+    attribute_object_type<&str, AttributeObjectType>,
+    alt!(
+        tag!("BU_") => { |_| AttributeObjectType::Node } |
+        tag!("BO_") => { |_| AttributeObjectType::Message } |
+        tag!("SG_") => { |_| AttributeObjectType::Signal } |
+        tag!("EV_") => { |_| AttributeObjectType::EnvironmentVariable }
+    )
+);
+
+named!(
+    attribute_type<&str, AttributeType>,
+    alt!(
+        do_parse!(
+            tag!("INT") >>
+            space >>
+            min: signed_integer >>
+            space >>
+            max: signed_integer >>
+            ( AttributeType::Int { min, max } )
+        ) |
+        do_parse!(
+            tag!("HEX") >>
+            space >>
+            min: signed_integer >>
+            space >>
+            max: signed_integer >>
+            ( AttributeType::Hex { min, max } )
+        ) |
+        do_parse!(
+            tag!("FLOAT") >>
+            space >>
+            min: double >>
+            space >>
+            max: double >>
+            ( AttributeType::Float { min, max } )
+        ) |
+        tag!("STRING") => { |_| AttributeType::String } |
+        do_parse!(
+            tag!("ENUM") >>
+            space0 >>
+            values: separated_list!(
+                delimited!(space0, tag!(","), space0),
+                quoted_str) >>
+            ( AttributeType::Enum(values) )
+        )
+    )
+);
+
+named!(pub attribute_definition<&str, AttributeDefinition>,
+    do_parse!(
+        tag!("BA_DEF_")   >>
+        space >>
+        object_type: opt!(
+            terminated!(
+                attribute_object_type,
+                space)) >>
+        name: quoted_str >>
+        space >>
+        value_type: attribute_type >>
+        space0 >>
+        tag!(";") >>
+        line_ending >>
+        ( AttributeDefinition {
+            object_type: object_type.unwrap_or(AttributeObjectType::Network),
+            name: name,
+            value_type: value_type
+        } )
+    )
+);
+
+named!(pub attribute_default<&str, AttributeDefault>,
+    do_parse!(
+        tag!("BA_DEF_DEF_")   >>
+        space >>
+        name: quoted_str >>
+        space >>
+        value: attribute_value >>
+        space0 >>
+        tag!(";") >>
+        line_ending >>
+        ( AttributeDefault {
+            name: name,
+            value: value
+        } )
+    )
+);
+
+named!(pub relation_attribute_default<&str, AttributeDefault>,
+    do_parse!(
+        tag!("BA_DEF_DEF_REL_")   >>
+        space >>
+        name: quoted_str >>
+        space >>
+        value: attribute_value >>
+        space0 >>
+        tag!(";") >>
+        line_ending >>
+        ( AttributeDefault {
+            name: name,
+            value: value
         } )
     )
 );
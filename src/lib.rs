@@ -44,3 +44,4 @@ extern crate nom;
 
 pub mod dbc;
 pub mod pgn;
+mod signal;
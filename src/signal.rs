@@ -0,0 +1,989 @@
+//! Signal decoding and encoding shared by the `dbc` and `pgn` libraries: the layout of signals
+//! within CAN messages, multiplexing, and the message parsing traits.
+
+use crate::dbc::{MultiplexIndicator, MultiplexSelector, ValueDefinition, ValueType};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// The collection of functions for parsing CAN messages `N` into their defined signal values.
+pub trait ParseMessage<N> {
+    /// Parses CAN message type `N` into generic `f32` signal value on success, or `None`
+    /// on failure.
+    fn parse_message(&self, msg: N) -> Option<f32>;
+
+    /// Returns a closure which parses CAN message type `N` into generic `f32` signal value on
+    /// success, or `None` on failure.
+    fn parser(&self) -> Box<dyn Fn(N) -> Option<f32>>;
+}
+
+/// The collection of functions for parsing CAN messages `N` into full precision `f64` signal
+/// values, e.g., for 32-bit counters which `f32` can't represent exactly.  Supports signals up
+/// to 64 bits wide.
+pub trait ParseMessageF64<N> {
+    /// Parses CAN message type `N` into `f64` signal value on success, or `None` on failure.
+    fn parse_message_f64(&self, msg: N) -> Option<f64>;
+
+    /// Returns a closure which parses CAN message type `N` into `f64` signal value on success,
+    /// or `None` on failure.
+    fn parser_f64(&self) -> Box<dyn Fn(N) -> Option<f64>>;
+}
+
+/// The collection of functions for parsing CAN messages `N` into `f32` signal values, reporting
+/// why the signal couldn't be decoded on failure.
+pub trait TryParseMessage<N> {
+    /// Parses CAN message type `N` into `f32` signal value, or `DecodeError` on failure.
+    fn try_parse_message(&self, msg: N) -> Result<f32, DecodeError>;
+}
+
+/// The collection of functions for extracting the raw, unscaled bits of signals from CAN
+/// messages `N`, using the same bit layout as `ParseMessage`.
+pub trait ParseMessageRaw<N> {
+    /// Extracts the unscaled bits of the signal from CAN message type `N`, or `None` on failure.
+    fn parse_raw_u64(&self, msg: N) -> Option<u64>;
+
+    /// Extracts the unscaled integer value of the signal from CAN message type `N`,
+    /// sign-extended for signed signals.  Returns `None` on failure, or for unsigned values
+    /// which don't fit in an `i64`.
+    fn parse_raw_i64(&self, msg: N) -> Option<i64>;
+}
+
+/// Conversion of a decoded signal into a natural Rust type, see `ParseMessageAs`.
+///
+/// Implement this for your own types to decode e.g. J1939 discrete states directly into an enum:
+///
+/// ```rust
+/// use canparse::pgn::{FromSignal, ParseMessageAs, SpnDefinition};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Switch {
+///     Off,
+///     On,
+///     Error,
+///     NotAvailable,
+/// }
+///
+/// impl FromSignal for Switch {
+///     fn from_signal(raw: u64, _value: f64) -> Option<Self> {
+///         match raw {
+///             0 => Some(Switch::Off),
+///             1 => Some(Switch::On),
+///             2 => Some(Switch::Error),
+///             3 => Some(Switch::NotAvailable),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// let spndef = SpnDefinition::new("Cruise_Control_Active".to_string(), 595, 2566844160,
+///     "".to_string(), 24, 2, true, false, 1.0, 0.0, 0.0, 3.0, "".to_string());
+/// let msg: [u8; 8] = [0xFF, 0xFF, 0xFF, 0xFD, 0xFF, 0xFF, 0xFF, 0xFF];
+/// assert_eq!(spndef.parse_message_as::<Switch>(&msg), Some(Switch::On));
+/// assert_eq!(spndef.parse_message_as::<bool>(&msg), Some(true));
+/// ```
+pub trait FromSignal: Sized {
+    /// Converts the unscaled bits `raw` and the scaled physical `value` of a signal into `Self`,
+    /// or `None` if the signal value can't be represented.
+    fn from_signal(raw: u64, value: f64) -> Option<Self>;
+
+    /// Converts a signal as `from_signal` does, along with the enumerated `label` of its raw
+    /// value (`VAL_`), if the definition has one covering it.  Ignores the label by default.
+    fn from_labeled_signal(raw: u64, value: f64, label: Option<&str>) -> Option<Self> {
+        let _ = label;
+        Self::from_signal(raw, value)
+    }
+}
+
+/// Rounded from the `f64` physical value.
+impl FromSignal for f32 {
+    fn from_signal(_raw: u64, value: f64) -> Option<Self> {
+        Some(value as f32)
+    }
+}
+
+impl FromSignal for f64 {
+    fn from_signal(_raw: u64, value: f64) -> Option<Self> {
+        Some(value)
+    }
+}
+
+/// The physical value, `None` if it isn't a whole number within range.
+impl FromSignal for i64 {
+    fn from_signal(_raw: u64, value: f64) -> Option<Self> {
+        if value.fract() == 0.0 && value >= i64::MIN as f64 && value < i64::MAX as f64 {
+            Some(value as i64)
+        } else {
+            None
+        }
+    }
+}
+
+/// The physical value, `None` if it isn't a non-negative whole number within range.
+impl FromSignal for u64 {
+    fn from_signal(_raw: u64, value: f64) -> Option<Self> {
+        if value.fract() == 0.0 && value >= 0.0 && value < u64::MAX as f64 {
+            Some(value as u64)
+        } else {
+            None
+        }
+    }
+}
+
+/// `false` for raw `0`, `true` for raw `1`, and `None` otherwise, so the J1939 error and not
+/// available states of a 2-bit flag aren't mistaken for `true`.
+impl FromSignal for bool {
+    fn from_signal(raw: u64, _value: f64) -> Option<Self> {
+        match raw {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+/// The enumerated label of the raw value (`VAL_`), `None` for unlabeled values.  Only
+/// `SpnDefinition` carries labels, so this is always `None` for `dbc::SignalDefinition`.
+impl FromSignal for String {
+    fn from_signal(_raw: u64, _value: f64) -> Option<Self> {
+        None
+    }
+
+    fn from_labeled_signal(_raw: u64, _value: f64, label: Option<&str>) -> Option<Self> {
+        label.map(str::to_string)
+    }
+}
+
+/// The collection of functions for parsing CAN messages `N` into any `FromSignal` type.
+pub trait ParseMessageAs<N> {
+    /// Parses CAN message type `N` into signal value type `T` on success, or `None` on failure.
+    fn parse_message_as<T: FromSignal>(&self, msg: N) -> Option<T>;
+}
+
+/// Outcome of a range-checked decode, see `ParseMessageChecked`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CheckedValue {
+    /// Physical value within the signal's `min_value`/`max_value`
+    InRange(f32),
+    /// Physical value outside of the signal's `min_value`/`max_value`, e.g., a sensor fault
+    OutOfRange(f32),
+}
+
+impl CheckedValue {
+    /// Returns the physical value, regardless of whether it is in range.
+    pub fn value(&self) -> f32 {
+        match *self {
+            CheckedValue::InRange(value) | CheckedValue::OutOfRange(value) => value,
+        }
+    }
+
+    /// Returns whether the value is within the signal's `min_value`/`max_value`.
+    pub fn is_in_range(&self) -> bool {
+        match *self {
+            CheckedValue::InRange(_) => true,
+            CheckedValue::OutOfRange(_) => false,
+        }
+    }
+}
+
+/// The collection of functions for parsing CAN messages `N` into their defined signal values,
+/// checked against the signal's `min_value`/`max_value`.  Limits are given half a `scale` step
+/// of tolerance for rounding, and signals whose limits are both zero are never out of range.
+pub trait ParseMessageChecked<N> {
+    /// Parses CAN message type `N` into a range-checked `f32` signal value on success, or
+    /// `None` on failure.
+    fn parse_message_checked(&self, msg: N) -> Option<CheckedValue>;
+}
+
+/// The collection of functions for encoding signal values into CAN messages `N`, the inverse of
+/// `ParseMessage`.
+pub trait EncodeMessage<N> {
+    /// Encodes generic `f32` signal value into CAN message type `N`, leaving the bits outside
+    /// of the signal untouched.
+    fn encode_message(&self, value: f32, msg: N) -> Result<(), EncodeError>;
+}
+
+/// Internal description of where a signal sits within CAN messages and how its raw bits scale
+/// into physical values, shared by `SpnDefinition` and `SignalDefinition` so that the message
+/// parsing traits are implemented once for both.
+#[derive(Debug, Clone, Copy)]
+pub struct SignalLayout {
+    pub start_bit: usize,
+    pub bit_len: usize,
+    pub little_endian: bool,
+    pub signed: bool,
+    pub value_type: ValueType,
+    pub scale: f32,
+    pub offset: f32,
+    pub min_value: f32,
+    pub max_value: f32,
+}
+
+/// Internal trait for signal definitions, which get the message parsing traits through their
+/// `SignalLayout`.  The `signal` module is private, so it can't be implemented outside of the
+/// crate.
+pub trait Layout {
+    /// Returns the signal's bit layout and scaling.
+    fn layout(&self) -> SignalLayout;
+
+    /// Returns the signal's enumerated labels for raw values (`VAL_`), if any.
+    fn value_definition(&self) -> Option<&ValueDefinition> {
+        None
+    }
+}
+
+impl SignalLayout {
+    /// Extracts the unscaled bits of the signal from CAN message slice, checking first that the
+    /// signal's width can hold its value type.
+    pub(crate) fn parse_raw(&self, msg: &[u8]) -> Result<u64, DecodeError> {
+        check_width(self.bit_len, self.value_type)?;
+        parse_raw(self.bit_len, self.start_bit, self.little_endian, msg)
+    }
+
+    /// Converts the unscaled bits of the signal into its `f32` physical value.
+    pub(crate) fn scale_raw(&self, raw: u64) -> f32 {
+        (raw_value(raw, self.bit_len, self.signed, self.value_type) as f32) * self.scale
+            + self.offset
+    }
+
+    /// Parses CAN message array into the signal's `f32` value, reading the whole array at once
+    /// when the signal fits within it.
+    fn parse_array(&self, msg: &[u8; 8]) -> Result<f32, DecodeError> {
+        check_width(self.bit_len, self.value_type)?;
+        let (msg64, shift): (u64, Option<usize>) = if self.little_endian {
+            (LittleEndian::read_u64(msg), Some(self.start_bit))
+        } else {
+            let end = motorola_msb(self.start_bit).checked_add(self.bit_len);
+            (
+                BigEndian::read_u64(msg),
+                end.and_then(|end| 64usize.checked_sub(end)),
+            )
+        };
+        let shift = match shift {
+            Some(shift) if shift.checked_add(self.bit_len).is_some_and(|end| end <= 64) => shift,
+            // Let the slice parser work out why the signal doesn't fit
+            _ => return self.parse_message(&msg[..]),
+        };
+
+        let raw = msg64.checked_shr(shift as u32).unwrap_or(0) & bit_mask(self.bit_len);
+
+        Ok(self.scale_raw(raw))
+    }
+
+    /// Parses CAN message slice into the signal's `f32` value.
+    fn parse_message(&self, msg: &[u8]) -> Result<f32, DecodeError> {
+        self.parse_raw(msg).map(|raw| self.scale_raw(raw))
+    }
+
+    /// Parses CAN message slice into the signal's full precision `f64` value, given `scale` and
+    /// `offset` already widened by `widen`.
+    fn parse_message_f64(&self, scale: f64, offset: f64, msg: &[u8]) -> Result<f64, DecodeError> {
+        self.parse_raw(msg)
+            .map(|raw| raw_value(raw, self.bit_len, self.signed, self.value_type) * scale + offset)
+    }
+
+    /// Extracts the unscaled integer value of the signal from CAN message slice, sign-extended
+    /// for signed signals.
+    fn parse_raw_i64(&self, msg: &[u8]) -> Option<i64> {
+        let raw = self.parse_raw(msg).ok()?;
+        if self.signed {
+            Some(sign_extend(raw, self.bit_len))
+        } else {
+            i64::try_from(raw).ok()
+        }
+    }
+
+    /// Returns the raw value which labels (`VAL_`) are keyed by, sign-extended for signed
+    /// signals.
+    pub(crate) fn label_value(&self, raw: u64) -> i64 {
+        if self.signed {
+            sign_extend(raw, self.bit_len)
+        } else {
+            raw as i64
+        }
+    }
+
+    /// Parses CAN message slice into the signal's value as `FromSignal` type `T`, labeled from
+    /// `value_definition`.
+    fn parse_message_as<T: FromSignal>(
+        &self,
+        value_definition: Option<&ValueDefinition>,
+        msg: &[u8],
+    ) -> Option<T> {
+        let raw = self.parse_raw(msg).ok()?;
+        let value = raw_value(raw, self.bit_len, self.signed, self.value_type) * widen(self.scale)
+            + widen(self.offset);
+        let label = value_definition.and_then(|values| values.get(self.label_value(raw)));
+        T::from_labeled_signal(raw, value, label)
+    }
+
+    /// Parses CAN message slice into the signal's `f32` value, checked against its
+    /// `min_value`/`max_value`.
+    fn parse_message_checked(&self, msg: &[u8]) -> Option<CheckedValue> {
+        let value = self.parse_message(msg).ok()?;
+        if self.in_range(value) {
+            Some(CheckedValue::InRange(value))
+        } else {
+            Some(CheckedValue::OutOfRange(value))
+        }
+    }
+
+    /// Checks a physical signal value against `min_value`/`max_value`, with half a `scale` step
+    /// of tolerance for rounded limits.  Signals whose limits are both zero are unbounded.
+    fn in_range(&self, value: f32) -> bool {
+        let tolerance = self.scale.abs() / 2.0;
+        let unbounded = self.min_value == 0.0 && self.max_value == 0.0;
+
+        unbounded || (self.min_value - tolerance <= value && value <= self.max_value + tolerance)
+    }
+
+    /// Converts a physical signal value into its unscaled bits, applying the reverse of `scale`
+    /// and `offset`.  Values outside of `min_value`/`max_value` are rejected.
+    fn encode_raw(&self, value: f32) -> Result<u64, EncodeError> {
+        if !value.is_finite() || !self.in_range(value) {
+            return Err(EncodeErrorKind::OutOfRange.into());
+        }
+
+        let unscaled = (f64::from(value) - f64::from(self.offset)) / f64::from(self.scale);
+        let bit_len = self.bit_len;
+        match self.value_type {
+            ValueType::Float32 if bit_len == 32 => Ok(u64::from((unscaled as f32).to_bits())),
+            ValueType::Float64 if bit_len == 64 => Ok(unscaled.to_bits()),
+            ValueType::Float32 | ValueType::Float64 => {
+                Err(EncodeErrorKind::UnsupportedWidth.into())
+            }
+            ValueType::Integer => {
+                if bit_len > 64 {
+                    return Err(EncodeErrorKind::UnsupportedWidth.into());
+                }
+                let rounded = unscaled.round();
+                let (min_raw, max_raw) = if self.signed {
+                    let half = 2f64.powi(bit_len as i32 - 1);
+                    (-half, half - 1.0)
+                } else {
+                    (0.0, 2f64.powi(bit_len as i32) - 1.0)
+                };
+                if !(min_raw..=max_raw).contains(&rounded) {
+                    return Err(EncodeErrorKind::Overflow.into());
+                }
+
+                if self.signed {
+                    Ok(rounded as i64 as u64 & bit_mask(bit_len))
+                } else {
+                    Ok(rounded as u64)
+                }
+            }
+        }
+    }
+
+    /// Encodes a physical signal value into CAN message slice, leaving the bits outside of the
+    /// signal untouched.
+    fn encode_message(&self, value: f32, msg: &mut [u8]) -> Result<(), EncodeError> {
+        let raw = self.encode_raw(value)?;
+        write_raw(raw, self.bit_len, self.start_bit, self.little_endian, msg)
+    }
+}
+
+impl<S: Layout> ParseMessage<&[u8; 8]> for S {
+    fn parse_message(&self, msg: &[u8; 8]) -> Option<f32> {
+        self.layout().parse_array(msg).ok()
+    }
+
+    fn parser(&self) -> Box<dyn Fn(&[u8; 8]) -> Option<f32>> {
+        let layout = self.layout();
+
+        Box::new(move |msg: &[u8; 8]| layout.parse_array(msg).ok())
+    }
+}
+
+impl<S: Layout> ParseMessage<&[u8]> for S {
+    fn parse_message(&self, msg: &[u8]) -> Option<f32> {
+        self.layout().parse_message(msg).ok()
+    }
+
+    fn parser(&self) -> Box<dyn Fn(&[u8]) -> Option<f32>> {
+        let layout = self.layout();
+
+        Box::new(move |msg: &[u8]| layout.parse_message(msg).ok())
+    }
+}
+
+impl<S: Layout> ParseMessageF64<&[u8; 8]> for S {
+    fn parse_message_f64(&self, msg: &[u8; 8]) -> Option<f64> {
+        ParseMessageF64::<&[u8]>::parse_message_f64(self, &msg[..])
+    }
+
+    fn parser_f64(&self) -> Box<dyn Fn(&[u8; 8]) -> Option<f64>> {
+        let layout = self.layout();
+        let scale = widen(layout.scale);
+        let offset = widen(layout.offset);
+
+        Box::new(move |msg: &[u8; 8]| layout.parse_message_f64(scale, offset, &msg[..]).ok())
+    }
+}
+
+impl<S: Layout> ParseMessageF64<&[u8]> for S {
+    fn parse_message_f64(&self, msg: &[u8]) -> Option<f64> {
+        let layout = self.layout();
+        layout
+            .parse_message_f64(widen(layout.scale), widen(layout.offset), msg)
+            .ok()
+    }
+
+    fn parser_f64(&self) -> Box<dyn Fn(&[u8]) -> Option<f64>> {
+        let layout = self.layout();
+        let scale = widen(layout.scale);
+        let offset = widen(layout.offset);
+
+        Box::new(move |msg: &[u8]| layout.parse_message_f64(scale, offset, msg).ok())
+    }
+}
+
+impl<S: Layout> TryParseMessage<&[u8; 8]> for S {
+    fn try_parse_message(&self, msg: &[u8; 8]) -> Result<f32, DecodeError> {
+        self.layout().parse_array(msg)
+    }
+}
+
+impl<S: Layout> TryParseMessage<&[u8]> for S {
+    fn try_parse_message(&self, msg: &[u8]) -> Result<f32, DecodeError> {
+        self.layout().parse_message(msg)
+    }
+}
+
+impl<S: Layout> ParseMessageRaw<&[u8; 8]> for S {
+    fn parse_raw_u64(&self, msg: &[u8; 8]) -> Option<u64> {
+        self.layout().parse_raw(&msg[..]).ok()
+    }
+
+    fn parse_raw_i64(&self, msg: &[u8; 8]) -> Option<i64> {
+        self.layout().parse_raw_i64(&msg[..])
+    }
+}
+
+impl<S: Layout> ParseMessageRaw<&[u8]> for S {
+    fn parse_raw_u64(&self, msg: &[u8]) -> Option<u64> {
+        self.layout().parse_raw(msg).ok()
+    }
+
+    fn parse_raw_i64(&self, msg: &[u8]) -> Option<i64> {
+        self.layout().parse_raw_i64(msg)
+    }
+}
+
+impl<S: Layout> ParseMessageAs<&[u8; 8]> for S {
+    fn parse_message_as<T: FromSignal>(&self, msg: &[u8; 8]) -> Option<T> {
+        self.layout()
+            .parse_message_as(self.value_definition(), &msg[..])
+    }
+}
+
+impl<S: Layout> ParseMessageAs<&[u8]> for S {
+    fn parse_message_as<T: FromSignal>(&self, msg: &[u8]) -> Option<T> {
+        self.layout().parse_message_as(self.value_definition(), msg)
+    }
+}
+
+impl<S: Layout> ParseMessageChecked<&[u8; 8]> for S {
+    fn parse_message_checked(&self, msg: &[u8; 8]) -> Option<CheckedValue> {
+        self.layout().parse_message_checked(&msg[..])
+    }
+}
+
+impl<S: Layout> ParseMessageChecked<&[u8]> for S {
+    fn parse_message_checked(&self, msg: &[u8]) -> Option<CheckedValue> {
+        self.layout().parse_message_checked(msg)
+    }
+}
+
+impl<S: Layout> EncodeMessage<&mut [u8; 8]> for S {
+    fn encode_message(&self, value: f32, msg: &mut [u8; 8]) -> Result<(), EncodeError> {
+        self.layout().encode_message(value, &mut msg[..])
+    }
+}
+
+impl<S: Layout> EncodeMessage<&mut [u8]> for S {
+    fn encode_message(&self, value: f32, msg: &mut [u8]) -> Result<(), EncodeError> {
+        self.layout().encode_message(value, msg)
+    }
+}
+
+/// Error returned on failure to encode a signal value into a CAN message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodeError {
+    kind: EncodeErrorKind,
+}
+
+impl EncodeError {
+    /// Returns the kind of encoding failure.
+    pub fn kind(&self) -> &EncodeErrorKind {
+        &self.kind
+    }
+
+    #[doc(hidden)]
+    pub fn __description(&self) -> &str {
+        self.kind.__description()
+    }
+
+    #[doc(hidden)]
+    pub fn __cause(&self) -> Option<&dyn Error> {
+        self.kind.__cause()
+    }
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.__description())
+    }
+}
+
+impl Error for EncodeError {
+    fn description(&self) -> &str {
+        self.__description()
+    }
+
+    fn cause(&self) -> Option<&dyn Error> {
+        self.__cause()
+    }
+}
+
+/// Type of `EncodeError`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeErrorKind {
+    /// Physical value outside of the signal's `min_value`/`max_value`
+    OutOfRange,
+    /// Unscaled value doesn't fit within the signal's `bit_len`
+    Overflow,
+    /// Signal extends past the end of the message
+    MessageTooShort,
+    /// Signal width not supported for its value type
+    UnsupportedWidth,
+    /// No signal with the given name in the message
+    UnknownSignal(String),
+    /// Signal with the given name overlaps another encoded signal
+    OverlappingSignals(String),
+}
+
+impl EncodeErrorKind {
+    #[doc(hidden)]
+    pub fn __description(&self) -> &str {
+        match self {
+            EncodeErrorKind::OutOfRange => "value outside of signal range",
+            EncodeErrorKind::Overflow => "unscaled value doesn't fit within signal length",
+            EncodeErrorKind::MessageTooShort => "signal extends past the end of the message",
+            EncodeErrorKind::UnsupportedWidth => "signal length not supported for value type",
+            EncodeErrorKind::UnknownSignal(_) => "no signal with the given name in the message",
+            EncodeErrorKind::OverlappingSignals(_) => "signal overlaps another encoded signal",
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn __cause(&self) -> Option<&dyn Error> {
+        None
+    }
+}
+
+impl Display for EncodeErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let s = self.__description();
+        write!(f, "{s}")
+    }
+}
+
+impl From<EncodeErrorKind> for EncodeError {
+    fn from(kind: EncodeErrorKind) -> Self {
+        EncodeError { kind }
+    }
+}
+
+/// Error returned on failure to decode a signal value from a CAN message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    kind: DecodeErrorKind,
+}
+
+impl DecodeError {
+    /// Returns the kind of decoding failure.
+    pub fn kind(&self) -> &DecodeErrorKind {
+        &self.kind
+    }
+
+    #[doc(hidden)]
+    pub fn __description(&self) -> &str {
+        self.kind.__description()
+    }
+
+    #[doc(hidden)]
+    pub fn __cause(&self) -> Option<&dyn Error> {
+        self.kind.__cause()
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.__description())
+    }
+}
+
+impl Error for DecodeError {
+    fn description(&self) -> &str {
+        self.__description()
+    }
+
+    fn cause(&self) -> Option<&dyn Error> {
+        self.__cause()
+    }
+}
+
+/// Type of `DecodeError`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeErrorKind {
+    /// Signal starts past the end of the message
+    OutOfBounds,
+    /// Signal starts within the message but extends past its end
+    PayloadTooShort,
+    /// Signal width not supported for its value type
+    UnsupportedWidth,
+    /// Signal isn't selected by the multiplexor value in the message
+    WrongMultiplexor,
+    /// No signal with the given name in the message
+    UnknownSignal(String),
+}
+
+impl DecodeErrorKind {
+    #[doc(hidden)]
+    pub fn __description(&self) -> &str {
+        match self {
+            DecodeErrorKind::OutOfBounds => "signal starts past the end of the message",
+            DecodeErrorKind::PayloadTooShort => "signal extends past the end of the message",
+            DecodeErrorKind::UnsupportedWidth => "signal length not supported for value type",
+            DecodeErrorKind::WrongMultiplexor => "signal not selected by the message multiplexor",
+            DecodeErrorKind::UnknownSignal(_) => "no signal with the given name in the message",
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn __cause(&self) -> Option<&dyn Error> {
+        None
+    }
+}
+
+impl Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let s = self.__description();
+        write!(f, "{s}")
+    }
+}
+
+impl From<DecodeErrorKind> for DecodeError {
+    fn from(kind: DecodeErrorKind) -> Self {
+        DecodeError { kind }
+    }
+}
+
+/// Internal function for widening an `f32` definition parameter to `f64` via its shortest
+/// decimal representation, so that e.g. a `0.05` scale doesn't become `0.05000000074505806`.
+/// Rounds to an increasing number of significant digits until the result converts back to the
+/// same `f32`, without going through a string.
+pub(crate) fn widen(value: f32) -> f64 {
+    let wide = f64::from(value);
+    if !value.is_normal() {
+        return wide;
+    }
+
+    let magnitude = wide.abs().log10().floor() as i32;
+    for digits in 1..=9 {
+        let places = digits - 1 - magnitude;
+        // Powers of ten up to 1e22 are exact, so the decimal is correctly rounded to `f64`
+        if places.abs() > 22 {
+            break;
+        }
+        let power = 10f64.powi(places.abs());
+        let decimal = if places >= 0 {
+            (wide * power).round() / power
+        } else {
+            (wide / power).round() * power
+        };
+        if decimal as f32 == value {
+            return decimal;
+        }
+    }
+
+    wide
+}
+
+/// Internal function for interpreting the unscaled bits of a signal as a number, sign-extending
+/// signed integers and reinterpreting the bits as an IEEE 754 float for `SIG_VALTYPE_` float
+/// signals.
+pub(crate) fn raw_value(raw: u64, bit_len: usize, signed: bool, value_type: ValueType) -> f64 {
+    match value_type {
+        ValueType::Integer if signed => sign_extend(raw, bit_len) as f64,
+        ValueType::Integer => raw as f64,
+        ValueType::Float32 => f64::from(f32::from_bits(raw as u32)),
+        ValueType::Float64 => f64::from_bits(raw),
+    }
+}
+
+/// Internal function for checking that a signal's `bit_len` can hold its value type.
+pub(crate) fn check_width(bit_len: usize, value_type: ValueType) -> Result<(), DecodeError> {
+    let supported = match value_type {
+        ValueType::Integer => bit_len <= 64,
+        ValueType::Float32 => bit_len == 32,
+        ValueType::Float64 => bit_len == 64,
+    };
+    if supported {
+        Ok(())
+    } else {
+        Err(DecodeErrorKind::UnsupportedWidth.into())
+    }
+}
+
+/// Internal function for sign-extending the two's complement value held in the lowest `bit_len`
+/// bits of `raw`.
+pub(crate) fn sign_extend(raw: u64, bit_len: usize) -> i64 {
+    match bit_len {
+        0 => 0,
+        1..=63 => {
+            let shift = 64 - bit_len as u32;
+            ((raw << shift) as i64) >> shift
+        }
+        _ => raw as i64,
+    }
+}
+
+/// Internal function for masking the lowest `bit_len` bits, supporting the full 64 bits of
+/// `SIG_VALTYPE_` double signals.
+pub(crate) fn bit_mask(bit_len: usize) -> u64 {
+    u64::MAX.checked_shr(64 - bit_len as u32).unwrap_or(0)
+}
+
+/// Internal function for extracting the unscaled bits of a signal from CAN message slices.
+///
+/// Little-endian (Intel) signals count `start_bit` as their least significant bit, with bit
+/// numbers increasing through each byte and on into the next.  Big-endian (Motorola) signals
+/// follow the DBC "sawtooth" numbering, where `start_bit` is their most significant bit and the
+/// signal continues from bit 0 of one byte into bit 7 of the next.
+pub(crate) fn parse_raw(
+    bit_len: usize,
+    start_bit: usize,
+    little_endian: bool,
+    msg: &[u8],
+) -> Result<u64, DecodeError> {
+    if bit_len > 64 {
+        return Err(DecodeErrorKind::UnsupportedWidth.into());
+    }
+    if bit_len == 0 {
+        return Ok(0);
+    }
+
+    let (first_byte, last_byte, shift) =
+        signal_window(bit_len, start_bit, little_endian).ok_or(DecodeErrorKind::OutOfBounds)?;
+    if first_byte >= msg.len() {
+        return Err(DecodeErrorKind::OutOfBounds.into());
+    }
+    let window = msg
+        .get(first_byte..=last_byte)
+        .ok_or(DecodeErrorKind::PayloadTooShort)?;
+
+    Ok((read_window(window, little_endian) >> shift) as u64 & bit_mask(bit_len))
+}
+
+/// Internal function for writing the unscaled bits of a signal into CAN message slices, leaving
+/// the bits outside of the signal untouched.  Uses the same bit numbering as `parse_raw`.
+pub(crate) fn write_raw(
+    raw: u64,
+    bit_len: usize,
+    start_bit: usize,
+    little_endian: bool,
+    msg: &mut [u8],
+) -> Result<(), EncodeError> {
+    if bit_len > 64 {
+        return Err(EncodeErrorKind::UnsupportedWidth.into());
+    }
+    if bit_len == 0 {
+        return Ok(());
+    }
+
+    let (first_byte, last_byte, shift) =
+        signal_window(bit_len, start_bit, little_endian).ok_or(EncodeErrorKind::MessageTooShort)?;
+    let window = msg
+        .get_mut(first_byte..=last_byte)
+        .ok_or(EncodeErrorKind::MessageTooShort)?;
+
+    let mask = u128::from(bit_mask(bit_len)) << shift;
+    let mut value = read_window(window, little_endian) & !mask;
+    value |= (u128::from(raw) << shift) & mask;
+
+    let len = window.len();
+    for (i, byte) in window.iter_mut().enumerate() {
+        let byte_shift = if little_endian { i } else { len - 1 - i };
+        *byte = (value >> (8 * byte_shift)) as u8;
+    }
+
+    Ok(())
+}
+
+/// Internal function for locating a signal within a message, returning the first and last
+/// bytes holding the signal and the number of bits below it within that byte window.  Returns
+/// `None` if the signal's position overflows.
+fn signal_window(
+    bit_len: usize,
+    start_bit: usize,
+    little_endian: bool,
+) -> Option<(usize, usize, usize)> {
+    if little_endian {
+        let end = start_bit.checked_add(bit_len)?;
+        Some((start_bit / 8, (end - 1) / 8, start_bit % 8))
+    } else {
+        let msb = motorola_msb(start_bit);
+        let end = msb.checked_add(bit_len)?;
+        let last_byte = (end - 1) / 8;
+        Some((msb / 8, last_byte, (last_byte + 1) * 8 - end))
+    }
+}
+
+/// Internal function for reading a byte window as an integer.  At most 9 bytes are needed, for
+/// a 64-bit signal which doesn't start on a byte boundary.
+fn read_window(window: &[u8], little_endian: bool) -> u128 {
+    if little_endian {
+        window
+            .iter()
+            .rev()
+            .fold(0, |acc, &byte| (acc << 8) | u128::from(byte))
+    } else {
+        window
+            .iter()
+            .fold(0, |acc, &byte| (acc << 8) | u128::from(byte))
+    }
+}
+
+/// Internal function for converting a big-endian (Motorola) `start_bit` in DBC sawtooth
+/// numbering into the position of the signal's most significant bit, counting bits MSB-first
+/// from the start of the message.
+fn motorola_msb(start_bit: usize) -> usize {
+    (start_bit / 8) * 8 + (7 - start_bit % 8)
+}
+
+/// Internal function resolving whether a signal is present in a frame given its multiplex
+/// indicator and selector, walking up its chain of multiplexors.  Without a selector, a
+/// multiplexed signal is selected by the message's top-level `multiplexor`.  `lookup` returns a
+/// signal's indicator, selector and raw value in the frame by name, and `depth` bounds the
+/// walk against cyclic definitions.
+fn is_multiplex_active<'a, F>(
+    multiplex_indicator: MultiplexIndicator,
+    multiplex_selector: Option<&'a MultiplexSelector>,
+    multiplexor: Option<&'a str>,
+    lookup: &F,
+    depth: usize,
+) -> bool
+where
+    F: Fn(
+        &str,
+    ) -> Option<(
+        MultiplexIndicator,
+        Option<&'a MultiplexSelector>,
+        Option<u64>,
+    )>,
+{
+    let value = match multiplex_indicator.multiplexed_value() {
+        Some(value) => value,
+        None => return true,
+    };
+    if depth == 0 {
+        return false;
+    }
+
+    let parent = match (multiplex_selector, multiplexor) {
+        (Some(selector), _) => selector.multiplexor_name.as_str(),
+        (None, Some(multiplexor)) => multiplexor,
+        (None, None) => return false,
+    };
+    match lookup(parent) {
+        Some((parent_indicator, parent_selector, Some(parent_value))) => {
+            let selected = match multiplex_selector {
+                Some(selector) => selector.contains(parent_value),
+                None => parent_value == value,
+            };
+            selected
+                && is_multiplex_active(
+                    parent_indicator,
+                    parent_selector,
+                    multiplexor,
+                    lookup,
+                    depth - 1,
+                )
+        }
+        _ => false,
+    }
+}
+
+/// Internal function selecting the signals present in a CAN message, out of a message's
+/// signals keyed by name.  `multiplexing` returns a signal's multiplex indicator, selector and
+/// raw value in the frame, or `None` for signals without a definition, and `multiplexor` names
+/// the message's top-level multiplexor.
+pub(crate) fn active_signals<'a, T, F>(
+    signals: &'a HashMap<String, T>,
+    multiplexor: Option<&'a str>,
+    multiplexing: F,
+) -> Vec<&'a T>
+where
+    F: Fn(
+        &'a T,
+    ) -> Option<(
+        MultiplexIndicator,
+        Option<&'a MultiplexSelector>,
+        Option<u64>,
+    )>,
+{
+    let lookup = |name: &str| signals.get(name).and_then(&multiplexing);
+
+    signals
+        .values()
+        .filter(|signal| match multiplexing(signal) {
+            Some((indicator, selector, _)) => {
+                is_multiplex_active(indicator, selector, multiplexor, &lookup, signals.len())
+            }
+            None => false,
+        })
+        .collect()
+}
+
+/// Internal function parsing CAN message slice into the `f32` values of the given signal
+/// definitions, keyed by signal name.
+pub(crate) fn parse_signals<'a, D, I>(definitions: I, msg: &[u8]) -> HashMap<&'a str, f32>
+where
+    D: for<'m> ParseMessage<&'m [u8]> + 'a,
+    I: IntoIterator<Item = (&'a str, &'a D)>,
+{
+    definitions
+        .into_iter()
+        .filter_map(|(name, definition)| {
+            ParseMessage::<&[u8]>::parse_message(definition, msg).map(|value| (name, value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::signal::widen;
+
+    #[test]
+    fn widen_shortest_decimal() {
+        for value in [
+            0.05f32,
+            0.125,
+            0.001,
+            3.0517578e-5,
+            -273.0,
+            1734.9688,
+            123456.79,
+        ] {
+            assert_eq!(widen(value), value.to_string().parse::<f64>().unwrap());
+        }
+        assert_eq!(widen(0.05), 0.05);
+        assert_eq!(widen(0.0), 0.0);
+        assert!(widen(f32::NAN).is_nan());
+    }
+}
@@ -27,3 +27,19 @@ fn pgnlib_from_dbc_file() {
         Err(std::io::ErrorKind::NotFound)
     )
 }
+
+#[test]
+fn pgnlib_decode() {
+    let lib = PgnLibrary::from_dbc_file("./tests/data/sample.dbc").unwrap();
+
+    // Decode every SPN of a frame containing engine speed
+    let msg: [u8; 8] = [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88];
+    let values = lib.decode(2364539904, &msg).unwrap();
+
+    let engine_speed = values
+        .iter()
+        .find(|value| value.name == "Engine_Speed")
+        .unwrap();
+    assert!((engine_speed.value - 2728.5).abs() < f32::EPSILON);
+    assert_eq!(engine_speed.units, "rpm");
+}